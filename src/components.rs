@@ -1,15 +1,83 @@
 use std::collections::HashSet;
 
 use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// A component that signals the intention of an entity to use an item
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActivateItem {
+    pub used_by: Entity,
+    pub item: Entity,
+}
+
+// A component that signals an alert raised at a point, waking the monsters around it to hunt for the
+// player, along with any members of the alerting monster's pack
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Alert {
+    pub source: Point,
+    pub target: Point,
+    pub radius: i32,
+    pub pack: Option<Pack>,
+}
+
+// An alert radius that reaches every tile of the map, however winding the way there from the alert
+pub const WHOLE_LEVEL_RADIUS: i32 = SCREEN_WIDTH * SCREEN_HEIGHT;
 
 // A component that denotes the Amulet of Yala
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct AmuletOfYala;
 
+// A component that denotes the ability to afflict an entity with a status effect, whether by drinking
+// a potion, springing a trap or being hit by a monster
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AppliesEffect {
+    pub kind: StatusEffectKind,
+    pub duration: i32,
+    pub magnitude: i32,
+}
+
+impl AppliesEffect {
+    pub fn afflict(&self, target: Entity) -> StatusEffect {
+        StatusEffect {
+            target,
+            kind: self.kind,
+            remaining_turns: self.duration,
+            magnitude: self.magnitude,
+        }
+    }
+}
+
+// A component that denotes an item carried by an entity
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Carried(pub Entity);
+
 // A component that denotes a behavior for chasing a player
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ChasingPlayer;
 
+// A component that denotes the damage an entity deals when it attacks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Damage(pub i32);
+
+// A component that denotes a monster that stays put until it's alerted or spots the player, after
+// which it gives chase
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dormant;
+
+// A component that denotes the experience an entity has gained, and the level it has reached
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Experience {
+    pub level: i32,
+    pub xp: i32,
+}
+
+impl Experience {
+    // The total experience needed to reach the next level
+    pub fn next_level_xp(&self) -> i32 {
+        self.level * 20
+    }
+}
+
 // A component that denotes an enemy
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Enemy;
@@ -40,6 +108,49 @@ impl FieldOfView {
     }
 }
 
+// A component that denotes an item that can be equipped into a slot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Equippable {
+    pub slot: EquipmentSlot,
+}
+
+// A component that denotes an item equipped by an entity, in one of its slots
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Equipped {
+    pub owner: Entity,
+    pub slot: EquipmentSlot,
+}
+
+// An enumeration of the slots into which items can be equipped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EquipmentSlot {
+    Weapon,
+    Armor,
+}
+
+// A component that denotes a behavior for fleeing from the player when health drops below a threshold
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Flees {
+    pub threshold: i32,
+}
+
+// A component that denotes an entity that is currently fleeing from the player
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fleeing;
+
+// A component that denotes an amount of gold, whether carried by an entity or lying in a pile
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gold(pub i32);
+
+// A component that denotes the ability to slam the ground, hitting and dazing the player from a few
+// tiles away. The slam needs a number of turns to recover before it can be used again
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GroundSlam {
+    pub radius: i32,
+    pub cooldown: i32,
+    pub ready_in: i32,
+}
+
 // A component that denotes health for an entity
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Health {
@@ -47,14 +158,95 @@ pub struct Health {
     pub max: i32,
 }
 
+// A component that denotes how many turns an entity can go before it needs to eat, counting down
+// each turn and below zero once it is starving
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hunger {
+    pub turns: i32,
+}
+
+impl Hunger {
+    pub const MAX: i32 = 1500;
+
+    pub fn state(&self) -> HungerState {
+        match self.turns {
+            t if t > 1000 => HungerState::WellFed,
+            t if t > 250 => HungerState::Normal,
+            t if t > 0 => HungerState::Hungry,
+            _ => HungerState::Starving,
+        }
+    }
+}
+
+// The stages of hunger an entity passes through as it goes without food
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HungerState {
+    WellFed,
+    Normal,
+    Hungry,
+    Starving,
+}
+
+impl HungerState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HungerState::WellFed => "Well Fed",
+            HungerState::Normal => "Normal",
+            HungerState::Hungry => "Hungry",
+            HungerState::Starving => "Starving",
+        }
+    }
+}
+
+// A component that denotes an entity, such as a trap, that can't be seen until it is discovered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hidden;
+
+// A component that denotes a point of interest, such as the last place the player was seen, that an
+// entity moves to investigate before searching around it for a number of turns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Investigating {
+    pub target: Point,
+    pub search_turns: i32,
+}
+
 // A component that denotes an item
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Item;
 
+// An enumeration of the kinds of loot a monster can drop when it dies
+//  * Gold drops a pile of up to the specified amount of gold
+//  * Potion drops a random potion
+//  * Equipment drops a random weapon or armor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Loot {
+    Gold(i32),
+    Potion,
+    Equipment,
+}
+
+// A component that denotes a table of weighted loot drops, rolled when a monster dies
+pub type LootTable = WeightedTable<Option<Loot>>;
+
+// A component that denotes an entity's pool of mana, which is spent to cast spells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mana {
+    pub current: i32,
+    pub max: i32,
+}
+
 // A component that denotes the name of an entity
 #[derive(Clone, PartialEq, Eq)]
 pub struct Name(pub String);
 
+// A component that denotes food, which staves off the hunger of whoever eats it for a number of turns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Nourishes(pub i32);
+
+// A component that denotes membership of a pack of monsters that acts as a group
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pack(pub usize);
+
 // A component that denotes a player
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Player;
@@ -63,6 +255,23 @@ pub struct Player;
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct MovingRandomly;
 
+// A component that denotes the price of an item in a shop
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Price(pub i32);
+
+// A component that denotes a projectile in flight, drawn along its path for a single frame
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Projectile {
+    pub path: Vec<Point>,
+}
+
+// A component that denotes the ability to attack from a distance
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangedAttack {
+    pub range: i32,
+    pub damage: i32,
+}
+
 // A component that denotes a renderable entity
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Render {
@@ -70,6 +279,156 @@ pub struct Render {
     pub glyph: FontCharType,
 }
 
+// A component that denotes the health an entity recovers by resting for a turn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RestHealing(pub i32);
+
+// A component that denotes a shopkeeper, who buys and sells items
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shopkeeper;
+
+// A component that denotes the bonuses to attack damage and damage reduction given by an equipped item
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StatBonus {
+    pub attack: i32,
+    pub defense: i32,
+}
+
+// A component that signals a sound made at a point, heard over a distance that depends on its volume
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sound {
+    pub source: Point,
+    pub volume: i32,
+}
+
+// The spells that can be cast:
+//  * Firebolt hurls a bolt of fire that burns everything around the point it strikes
+//  * Blink teleports the caster to a point they can see
+//  * Heal restores the caster's health
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Spell {
+    Firebolt,
+    Blink,
+    Heal,
+}
+
+impl Spell {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Spell::Firebolt => "Firebolt",
+            Spell::Blink => "Blink",
+            Spell::Heal => "Heal",
+        }
+    }
+
+    // The mana it takes to cast the spell
+    pub fn cost(&self) -> i32 {
+        match self {
+            Spell::Firebolt => 3,
+            Spell::Blink => 4,
+            Spell::Heal => 5,
+        }
+    }
+
+    // How far from the caster the spell can be aimed, with zero meaning it can only target the caster
+    pub fn range(&self) -> i32 {
+        match self {
+            Spell::Firebolt => 6,
+            Spell::Blink => 8,
+            Spell::Heal => 0,
+        }
+    }
+
+    // The radius of the area the spell affects around its target
+    pub fn radius(&self) -> i32 {
+        match self {
+            Spell::Firebolt => 1,
+            _ => 0,
+        }
+    }
+
+    // Gets the points affected by the spell when cast at a target
+    pub fn area(&self, target: Point) -> Vec<Point> {
+        let radius = self.radius();
+        let mut area = Vec::new();
+        for y in -radius..=radius {
+            for x in -radius..=radius {
+                area.push(target + Point::new(x, y));
+            }
+        }
+        area
+    }
+}
+
+// A component that denotes an entity whose melee attacks also strike every other enemy next to it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cleave;
+
+// A component that denotes an entity that spots hidden traps as soon as they come into view
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrapSense;
+
+// A component that denotes the spells an entity knows
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Spellbook(pub Vec<Spell>);
+
+// A component that denotes a status effect afflicting an entity, which wears off after a number of turns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StatusEffect {
+    pub target: Entity,
+    pub kind: StatusEffectKind,
+    pub remaining_turns: i32,
+    pub magnitude: i32,
+}
+
+// An enumeration of the kinds of status effect
+//  * Poison drains `magnitude` health each turn
+//  * Confusion makes movement random, `magnitude` times in ten
+//  * Regeneration restores `magnitude` health each turn
+//  * Haste lets the player act `magnitude` extra times for each monster turn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusEffectKind {
+    Poison,
+    Confusion,
+    Regeneration,
+    Haste,
+}
+
+impl StatusEffectKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusEffectKind::Poison => "Poisoned",
+            StatusEffectKind::Confusion => "Confused",
+            StatusEffectKind::Regeneration => "Regenerating",
+            StatusEffectKind::Haste => "Hasted",
+        }
+    }
+}
+
+// A component that signals a monster has been slain, to be counted and rewarded with experience
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Slain {
+    pub name: String,
+    pub xp: i32,
+}
+
+// A component that denotes a trap, which springs on whatever steps onto it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Trap {
+    pub kind: TrapKind,
+}
+
+// The kinds of trap:
+//  * A spike pit deals the trap's damage to its victim
+//  * A teleport plate sends its victim to a random spot on the map
+//  * An alarm plate wakes every monster on the map and sends them to investigate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrapKind {
+    SpikePit,
+    Teleport,
+    Alarm,
+}
+
 // A component that signals the intention of an entity to attack
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct WantsToAttack {
@@ -77,6 +436,14 @@ pub struct WantsToAttack {
     pub victim: Entity,
 }
 
+// A component that signals the intention of an entity to cast a spell at a point
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WantsToCast {
+    pub caster: Entity,
+    pub spell: Spell,
+    pub target: Point,
+}
+
 // A component that signals the intention of an entity to move
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct WantsToMove {
@@ -0,0 +1,139 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// The file the difficulty presets are read from
+const DIFFICULTY_FILE: &str = "resources/difficulty.ron";
+
+// The player's health and sight at normal difficulty, against which the presets adjust the class's
+const NORMAL_HEALTH: i32 = 10;
+const NORMAL_FOV: i32 = 8;
+
+// A difficulty preset a run is played at, recorded in saves and scores so that a run can be replayed
+// and compared even after the presets are changed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Difficulty {
+    pub name: String,
+    // The number of monsters and items spawned on the map
+    pub num_monsters: usize,
+    // The extra health every monster spawns with
+    pub monster_health_bonus: i32,
+    // The health the player starts with, scaling the class's health against that of a normal game
+    pub player_health: i32,
+    // How far the player can see, adding to or taking from the class's sight against a normal game
+    pub player_fov: i32,
+    // The health the player recovers by waiting a turn
+    pub wait_heal: i32,
+}
+
+impl Difficulty {
+    // Loads the difficulty presets, falling back to the built in ones if the file is missing or
+    // can't be read
+    pub fn load_presets() -> Vec<Self> {
+        std::fs::read_to_string(DIFFICULTY_FILE)
+            .ok()
+            .and_then(|presets| ron::from_str::<Vec<Self>>(&presets).ok())
+            .filter(|presets| !presets.is_empty())
+            .unwrap_or_else(Self::default_presets)
+    }
+
+    // The number of monsters and items spawned on a level, growing by a quarter with each level down
+    pub fn monsters_at_depth(&self, depth: i32) -> usize {
+        self.num_monsters * (depth as usize + 3) / 4
+    }
+
+    // The health a player of a class starts with at this difficulty
+    pub fn class_health(&self, class: &PlayerClass) -> i32 {
+        i32::max(1, class.health * self.player_health / NORMAL_HEALTH)
+    }
+
+    // How far a player of a class can see at this difficulty
+    pub fn class_fov(&self, class: &PlayerClass) -> i32 {
+        i32::max(1, class.fov + self.player_fov - NORMAL_FOV)
+    }
+
+    fn default_presets() -> Vec<Self> {
+        vec![
+            Self::new("Easy", 30, 0, 15, 10, 2),
+            Self::new("Normal", 50, 0, 10, 8, 1),
+            Self::new("Hard", 65, 1, 7, 6, 0),
+        ]
+    }
+
+    fn new(
+        name: &str,
+        num_monsters: usize,
+        monster_health_bonus: i32,
+        player_health: i32,
+        player_fov: i32,
+        wait_heal: i32,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            num_monsters,
+            monster_health_bonus,
+            player_health,
+            player_fov,
+            wait_heal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn monsters_at_depth_grows_by_a_quarter_each_level() {
+        let normal = Difficulty::new("Normal", 40, 0, 10, 8, 1);
+        assert_eq!(normal.monsters_at_depth(1), 40);
+        assert_eq!(normal.monsters_at_depth(2), 50);
+        assert_eq!(normal.monsters_at_depth(5), 80);
+    }
+
+    fn class(health: i32, fov: i32) -> PlayerClass {
+        PlayerClass {
+            health,
+            fov,
+            ..PlayerClass::default()
+        }
+    }
+
+    #[test]
+    fn class_health_scales_against_a_normal_game() {
+        let warrior = class(12, 7);
+        assert_eq!(
+            Difficulty::new("Normal", 50, 0, 10, 8, 1).class_health(&warrior),
+            12
+        );
+        assert_eq!(
+            Difficulty::new("Easy", 30, 0, 15, 10, 2).class_health(&warrior),
+            18
+        );
+        assert_eq!(
+            Difficulty::new("Hard", 65, 1, 7, 6, 0).class_health(&warrior),
+            8
+        );
+        assert_eq!(
+            Difficulty::new("Cruel", 80, 2, 0, 1, 0).class_health(&warrior),
+            1
+        );
+    }
+
+    #[test]
+    fn class_fov_adjusts_against_a_normal_game() {
+        let rogue = class(10, 10);
+        assert_eq!(
+            Difficulty::new("Normal", 50, 0, 10, 8, 1).class_fov(&rogue),
+            10
+        );
+        assert_eq!(
+            Difficulty::new("Easy", 30, 0, 15, 10, 2).class_fov(&rogue),
+            12
+        );
+        assert_eq!(Difficulty::new("Hard", 65, 1, 7, 6, 0).class_fov(&rogue), 8);
+        assert_eq!(
+            Difficulty::new("Cruel", 80, 2, 0, -5, 0).class_fov(&rogue),
+            1
+        );
+    }
+}
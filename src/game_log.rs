@@ -0,0 +1,21 @@
+// A log of the messages describing what has happened over the course of a run
+pub struct GameLog {
+    pub entries: Vec<String>,
+}
+
+impl GameLog {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn add<S: ToString>(&mut self, message: S) {
+        self.entries.push(message.to_string());
+    }
+
+    // Gets up to the specified number of the most recent entries, oldest first
+    pub fn recent(&self, count: usize) -> &[String] {
+        &self.entries[self.entries.len().saturating_sub(count)..]
+    }
+}
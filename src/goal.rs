@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+// What the player must do with the Amulet of Yala to win the run, either claim it where it lies or
+// carry it all the way back up to the surface
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Goal {
+    #[default]
+    ClaimAmulet,
+    EscapeWithAmulet,
+}
+
+impl Goal {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Goal::ClaimAmulet => "Claim the Amulet",
+            Goal::EscapeWithAmulet => "Escape with the Amulet",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Goal::ClaimAmulet => Goal::EscapeWithAmulet,
+            Goal::EscapeWithAmulet => Goal::ClaimAmulet,
+        }
+    }
+}
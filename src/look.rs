@@ -0,0 +1,14 @@
+use crate::prelude::*;
+
+// The point the cursor is over while looking around the map
+pub struct Look {
+    pub cursor: Point,
+}
+
+impl Look {
+    pub fn new() -> Self {
+        Self {
+            cursor: Point::zero(),
+        }
+    }
+}
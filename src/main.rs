@@ -1,10 +1,25 @@
 mod components;
+mod difficulty;
+mod game_log;
+mod goal;
+mod look;
 mod map;
 mod map_builder;
+mod menu;
+mod minimap;
+mod morgue;
+mod options;
+mod player_class;
+mod replay;
+mod run_stats;
+mod save;
+mod scores;
 mod spawner;
 mod systems;
+mod targeting;
 mod turn_state;
 mod viewport;
+mod weighted_table;
 
 mod prelude {
     pub use bracket_lib::prelude::*;
@@ -13,12 +28,27 @@ mod prelude {
     pub use legion::*;
 
     pub use crate::components::*;
+    pub use crate::difficulty::*;
+    pub use crate::game_log::*;
+    pub use crate::goal::*;
+    pub use crate::look::*;
     pub use crate::map::*;
     pub use crate::map_builder::*;
+    pub use crate::menu::*;
+    pub use crate::minimap::*;
+    pub use crate::morgue::*;
+    pub use crate::options::*;
+    pub use crate::player_class::*;
+    pub use crate::replay::*;
+    pub use crate::run_stats::*;
+    pub use crate::save::*;
+    pub use crate::scores::*;
     pub use crate::spawner::*;
     pub use crate::systems::*;
+    pub use crate::targeting::*;
     pub use crate::turn_state::*;
     pub use crate::viewport::*;
+    pub use crate::weighted_table::*;
 
     pub const SCREEN_WIDTH: i32 = 80;
     pub const SCREEN_HEIGHT: i32 = 50;
@@ -27,65 +57,847 @@ mod prelude {
 }
 
 use prelude::*;
+use std::path::{Path, PathBuf};
 
 struct State {
     ecs: World,
     resources: Resources,
     input_systems: Schedule,
+    inventory_systems: Schedule,
+    character_sheet_systems: Schedule,
+    shop_systems: Schedule,
+    targeting_systems: Schedule,
+    look_systems: Schedule,
     player_systems: Schedule,
     monster_systems: Schedule,
+    options: Options,
+    difficulties: Vec<Difficulty>,
+    difficulty: usize,
+    goal: Goal,
+    classes: Vec<PlayerClass>,
+    class: usize,
+    seed_entry: String,
+    menu_selection: usize,
+    message_scroll: usize,
+    run: SaveGame,
+    high_scores: HighScores,
+    last_rank: Option<usize>,
+    morgue_file: Option<PathBuf>,
+    replay: Option<Replay>,
 }
 
 impl State {
     fn new() -> Self {
-        let mut rng = RandomNumberGenerator::new();
+        // Start out on the main menu, with no game in progress
+        let mut resources = Resources::default();
+        resources.insert(TurnState::MainMenu);
+
+        // Start out with the normal difficulty selected, if there is one
+        let difficulties = Difficulty::load_presets();
+        let difficulty = difficulties
+            .iter()
+            .position(|preset| preset.name == "Normal")
+            .unwrap_or(0);
+        let classes = PlayerClass::load_classes();
+        let run = SaveGame::new(
+            0,
+            difficulties[difficulty].clone(),
+            Goal::ClaimAmulet,
+            classes[0].clone(),
+            ThemeChoice::Random,
+        );
+
+        Self {
+            ecs: World::default(),
+            resources,
+            input_systems: build_input_scheduler(),
+            inventory_systems: build_inventory_scheduler(),
+            character_sheet_systems: build_character_sheet_scheduler(),
+            shop_systems: build_shop_scheduler(),
+            targeting_systems: build_targeting_scheduler(),
+            look_systems: build_look_scheduler(),
+            player_systems: build_player_scheduler(),
+            monster_systems: build_monster_scheduler(),
+            options: Options::load(),
+            difficulties,
+            difficulty,
+            goal: Goal::ClaimAmulet,
+            classes,
+            class: 0,
+            seed_entry: String::new(),
+            menu_selection: 0,
+            message_scroll: 0,
+            run,
+            high_scores: HighScores::load(),
+            last_rank: None,
+            morgue_file: None,
+            replay: None,
+        }
+    }
+
+    // Starts a run from its first turn, with the seed, difficulty, goal and class recorded for it
+    fn start_game(&mut self, run: SaveGame) {
+        // Start recording the run
+        self.run = SaveGame {
+            inputs: Vec::new(),
+            ..run
+        };
+        let run = self.run.clone();
+        self.replay = None;
+        self.last_rank = None;
+        self.morgue_file = None;
+
+        // Create a new world
+        self.ecs = World::default();
+
+        // Create a new resource manager
+        self.resources = Resources::default();
+
+        // Spawn the player, who is placed on the map once it's built
+        spawn_player(&mut self.ecs, Point::zero(), &run.difficulty, &run.class);
 
+        // Build the first level, from a seeded random number generator so that the run can be
+        // replayed
+        let mut rng = RandomNumberGenerator::seeded(run.seed);
+        self.build_level(&mut rng, 1, &run.difficulty, run.goal, false);
+
+        // Add the turn state and the rest of the run's resources to the resource manager
+        self.resources.insert(TurnState::AwaitingInput);
+        self.resources.insert(Targeting::new());
+        self.resources.insert(Look::new());
+        self.resources.insert(Point::zero());
+        self.resources.insert(GameLog::new());
+        self.resources.insert(RunStats::new());
+        self.resources.insert(Minimap::new());
+        self.resources.insert(run.goal);
+
+        // Add the random number generator to the resources, for systems that need one
+        self.resources.insert(rng);
+    }
+
+    // Builds a level of the dungeon, placing the player at its start and populating it with monsters
+    // and items that get more numerous and dangerous the deeper it is. A player returning to the
+    // surface with the amulet comes up at the far end of the level instead, and has to fight their
+    // way back to the stairs up through more monsters than before
+    fn build_level(
+        &mut self,
+        rng: &mut RandomNumberGenerator,
+        depth: i32,
+        difficulty: &Difficulty,
+        goal: Goal,
+        returning: bool,
+    ) {
         // Build a new map
-        let map_builder = MapBuilder::new(&mut rng);
+        let mut num_monsters = difficulty.monsters_at_depth(depth);
+        if returning {
+            num_monsters = num_monsters * 3 / 2;
+        }
+        let mut map_builder = MapBuilder::new(rng, num_monsters);
+        // The theme is the one recorded for the run, so that it plays out the same when continued or
+        // replayed after the options have changed
+        if let Some(theme) = self.run.theme.theme() {
+            map_builder.theme = theme;
+        }
+
+        // Move the player to the start of the level, or the end of it on the way back up
+        let arrival = if returning {
+            map_builder.amulet_start
+        } else {
+            map_builder.player_start
+        };
+        <&mut Point>::query()
+            .filter(component::<Player>())
+            .iter_mut(&mut self.ecs)
+            .for_each(|pos| *pos = arrival);
 
-        // Create a new ECS instance, into which we'll be sticking entities
-        let mut ecs = World::default();
+        // Keep the monsters and traps away from where the player comes up, as the spawns are only
+        // kept clear of the start of the level
+        if returning {
+            map_builder
+                .monster_spawns
+                .retain(|pos| DistanceAlg::Pythagoras.distance2d(arrival, *pos) > 10.0);
+            map_builder.trap_spawns.retain(|pos| *pos != arrival);
+        }
 
-        // Spawn the player
-        spawn_player(&mut ecs, map_builder.player_start);
+        // When the player has to escape with the amulet, the way out is up the stairs at the start
+        // of the final level and every level they return through
+        if goal == Goal::EscapeWithAmulet && (returning || depth == FINAL_DEPTH) {
+            let idx = map_idx(map_builder.player_start.x, map_builder.player_start.y);
+            map_builder.map.tiles[idx] = TileType::UpStairs;
+        }
 
-        // Spawn the Amulet of Yala
-        spawn_amulet_of_yala(&mut ecs, map_builder.amulet_start);
+        // Place the amulet on the final level, guarded by the boss, and the stairs down on the others.
+        // A player returning with the amulet has no reason to go back down
+        if depth == FINAL_DEPTH && !returning {
+            spawn_amulet_of_yala(&mut self.ecs, map_builder.amulet_start);
+            let lair = [
+                Point::new(-1, 0),
+                Point::new(1, 0),
+                Point::new(0, -1),
+                Point::new(0, 1),
+                Point::new(-1, -1),
+                Point::new(1, -1),
+                Point::new(-1, 1),
+                Point::new(1, 1),
+            ]
+            .iter()
+            .map(|delta| map_builder.amulet_start + *delta)
+            .find(|pt| {
+                map_builder.map.can_enter_tile(*pt) && !map_builder.monster_spawns.contains(pt)
+            })
+            .unwrap_or(map_builder.amulet_start);
+            spawn_boss(&mut self.ecs, lair);
+        } else if !returning {
+            let idx = map_idx(map_builder.amulet_start.x, map_builder.amulet_start.y);
+            map_builder.map.tiles[idx] = TileType::Exit;
+        }
 
-        // Spawn monsters, one in each room, except for the first (where the player spawns)
+        // Spawn the monsters and items, from the monsters that live in the level's theme
+        let monsters = map_builder.theme.monsters(depth);
         map_builder
             .monster_spawns
             .iter()
-            .for_each(|pos| spawn_monster(&mut ecs, &mut rng, *pos));
+            .for_each(|pos| spawn_entity(&mut self.ecs, rng, *pos, &monsters));
 
-        // Add the map and viewport to the resources
-        let mut resources = Resources::default();
-        resources.insert(map_builder.map);
-        resources.insert(Viewport::new(map_builder.player_start));
+        // Spawn the war band guarding the fortress
+        spawn_war_band(&mut self.ecs, Pack(0), &map_builder.war_band_spawns);
+
+        // Toughen up the monsters on harder difficulties
+        <&mut Health>::query()
+            .filter(component::<Enemy>())
+            .iter_mut(&mut self.ecs)
+            .for_each(|health| {
+                health.max += difficulty.monster_health_bonus;
+                health.current = health.max;
+            });
 
-        // Add the initial awaiting turn state to the resources
-        resources.insert(TurnState::AwaitingInput);
+        // The monsters on the way back up know the amulet has been taken, and gather to cut off the
+        // player's escape
+        if returning {
+            self.ecs.push((
+                (),
+                Alert {
+                    source: map_builder.player_start,
+                    target: map_builder.player_start,
+                    radius: WHOLE_LEVEL_RADIUS,
+                    pack: None,
+                },
+            ));
+        }
 
-        // Add the theme to the resources
-        resources.insert(map_builder.theme);
+        // Spawn the shopkeeper
+        if let Some(pos) = map_builder.shopkeeper_start {
+            spawn_shopkeeper(&mut self.ecs, rng, pos);
+        }
 
-        Self {
-            ecs,
-            resources,
-            input_systems: build_input_scheduler(),
-            player_systems: build_player_scheduler(),
-            monster_systems: build_monster_scheduler(),
+        // Hide the traps
+        map_builder
+            .trap_spawns
+            .iter()
+            .for_each(|pos| spawn_trap(&mut self.ecs, rng, *pos));
+
+        // Add the map, viewport and theme to the resource manager
+        self.resources.insert(map_builder.map);
+        self.resources
+            .insert(Viewport::new(arrival, self.options.camera.deadzone()));
+        self.resources.insert(map_builder.theme);
+    }
+
+    // Takes the player down the stairs to the next level, along with everything they carry
+    fn advance_level(&mut self) {
+        self.clear_level();
+        let depth = {
+            let mut stats = self.resources.get_mut::<RunStats>().unwrap();
+            stats.depth += 1;
+            stats.depth
+        };
+        self.enter_level(depth, false);
+        self.resources
+            .get_mut::<GameLog>()
+            .unwrap()
+            .add(format!("You descend to depth {}.", depth));
+    }
+
+    // Takes the player back up the stairs towards the surface with the amulet. Climbing out of the
+    // first level wins the run
+    fn retreat_level(&mut self) {
+        // The way up is only open to a player who still has the amulet
+        let player = *<Entity>::query()
+            .filter(component::<Player>())
+            .iter(&self.ecs)
+            .next()
+            .unwrap();
+        let has_amulet = <&Carried>::query()
+            .filter(component::<AmuletOfYala>())
+            .iter(&self.ecs)
+            .any(|carried| carried.0 == player);
+        if !has_amulet {
+            self.resources.insert(TurnState::AwaitingInput);
+            return;
+        }
+
+        let depth = {
+            let mut stats = self.resources.get_mut::<RunStats>().unwrap();
+            if stats.depth == 1 {
+                drop(stats);
+                self.resources.insert(TurnState::Victory);
+                return;
+            }
+            stats.depth -= 1;
+            stats.depth
+        };
+        self.clear_level();
+        self.enter_level(depth, true);
+        self.resources
+            .get_mut::<GameLog>()
+            .unwrap()
+            .add(format!("You climb back up to depth {}.", depth));
+    }
+
+    // Removes everything but the player, their items and the status effects afflicting them, ready
+    // for the next level to be built
+    fn clear_level(&mut self) {
+        let player = *<Entity>::query()
+            .filter(component::<Player>())
+            .iter(&self.ecs)
+            .next()
+            .unwrap();
+
+        let mut keep = std::collections::HashSet::new();
+        keep.insert(player);
+        <(Entity, &Carried)>::query()
+            .iter(&self.ecs)
+            .filter(|(_, carried)| carried.0 == player)
+            .for_each(|(entity, _)| {
+                keep.insert(*entity);
+            });
+        <(Entity, &StatusEffect)>::query()
+            .iter(&self.ecs)
+            .filter(|(_, effect)| effect.target == player)
+            .for_each(|(entity, _)| {
+                keep.insert(*entity);
+            });
+        let mut commands = CommandBuffer::new(&self.ecs);
+        <Entity>::query()
+            .iter(&self.ecs)
+            .filter(|entity| !keep.contains(*entity))
+            .for_each(|entity| commands.remove(*entity));
+        commands.flush(&mut self.ecs);
+
+        // The player needs to look around the new level
+        <&mut FieldOfView>::query()
+            .iter_mut(&mut self.ecs)
+            .for_each(|fov| fov.is_dirty = true);
+    }
+
+    // Builds the level at a depth with the run's random number generator, and hands control back to
+    // the player
+    fn enter_level(&mut self, depth: i32, returning: bool) {
+        let mut rng = self.resources.remove::<RandomNumberGenerator>().unwrap();
+        let difficulty = self.run.difficulty.clone();
+        self.build_level(&mut rng, depth, &difficulty, self.run.goal, returning);
+        self.resources.insert(rng);
+        self.resources.insert(TurnState::AwaitingInput);
+    }
+
+    // Restores the saved run, rebuilding its dungeon and replaying every key the player pressed
+    fn continue_game(&mut self, ctx: &mut BTerm) {
+        if let Some(save) = SaveGame::load() {
+            self.start_game(save.clone());
+            for input in save.inputs.iter() {
+                self.play_out_turn(ctx);
+                self.execute(Some(input.key));
+                flush_draw_buffer(ctx);
+            }
+            self.run = save;
+        }
+    }
+
+    // Plays out the turn until the game is waiting on the player again
+    fn play_out_turn(&mut self, ctx: &mut BTerm) {
+        while !self.turn_state().is_awaiting_player() && !self.turn_state().is_over() {
+            self.execute(None);
+            flush_draw_buffer(ctx);
+        }
+    }
+
+    // Starts watching a recorded run, rebuilding its dungeon and feeding it the recorded keys
+    fn start_replay(&mut self, path: &Path) {
+        if let Some(recording) = SaveGame::load_from(path) {
+            let inputs = recording.inputs.clone();
+            self.start_game(recording);
+            self.replay = Some(Replay::new(inputs));
+        }
+    }
+
+    // Plays back the next recorded key once it's due, following the speed, pause and step controls
+    fn replay_tick(&mut self, ctx: &mut BTerm) {
+        let awaiting_player = self.turn_state().is_awaiting_player();
+        let turn = self.resources.get::<RunStats>().unwrap().turns;
+        let replay = self.replay.as_mut().unwrap();
+        let mut step = false;
+        match ctx.key {
+            Some(VirtualKeyCode::Escape) => {
+                self.replay = None;
+                self.set_turn_state(TurnState::MainMenu);
+                return;
+            }
+            Some(VirtualKeyCode::Space) => replay.paused = !replay.paused,
+            Some(VirtualKeyCode::Right) | Some(VirtualKeyCode::Period) => {
+                replay.paused = true;
+                step = true;
+            }
+            Some(VirtualKeyCode::Up) => replay.faster(),
+            Some(VirtualKeyCode::Down) => replay.slower(),
+            _ => {}
+        }
+
+        // Play out the monsters' turns as they come, only holding back the player's keys
+        if !awaiting_player {
+            self.execute(None);
+        } else {
+            match replay.advance(step) {
+                Some(input) => {
+                    if turn != input.turn && !replay.out_of_sync {
+                        replay.out_of_sync = true;
+                        replay.paused = true;
+                        self.resources.get_mut::<GameLog>().unwrap().add(format!(
+                            "The replay is out of sync: the key was pressed on turn {}, not {}.",
+                            input.turn, turn
+                        ));
+                    }
+                    self.execute(Some(input.key));
+                }
+                None => self.execute(None),
+            }
+        }
+
+        // Show how far through the replay is, and how to control it
+        let replay = self.replay.as_ref().unwrap();
+        let status = if replay.is_finished() {
+            "finished"
+        } else if replay.paused {
+            "paused"
+        } else {
+            "playing"
+        };
+        let mut draw_batch = DrawBatch::new();
+        draw_batch.target(2);
+        draw_batch.print_color_centered(
+            SCREEN_HEIGHT * 2 - 1,
+            format!(
+                " Replay {} ({} / {}, speed {}): Space to pause, Right to step, Up/Down for speed, Escape to stop. ",
+                status,
+                replay.next,
+                replay.inputs.len(),
+                replay.speed + 1
+            ),
+            ColorPair::new(YELLOW, BLACK),
+        );
+        draw_batch.submit(12000).expect("Batch error");
+    }
+
+    fn turn_state(&self) -> TurnState {
+        *self.resources.get::<TurnState>().unwrap()
+    }
+
+    fn set_turn_state(&mut self, turn_state: TurnState) {
+        self.menu_selection = 0;
+        self.resources.insert(turn_state);
+    }
+
+    // Executes the systems for the current turn state, with the key pressed, if any
+    fn execute(&mut self, key: Option<VirtualKeyCode>) {
+        self.resources.insert(key);
+        match self.turn_state() {
+            TurnState::AwaitingInput => {
+                self.input_systems
+                    .execute(&mut self.ecs, &mut self.resources);
+            }
+            TurnState::ShowingInventory => {
+                self.inventory_systems
+                    .execute(&mut self.ecs, &mut self.resources);
+            }
+            TurnState::ShowingCharacter => {
+                self.character_sheet_systems
+                    .execute(&mut self.ecs, &mut self.resources);
+            }
+            TurnState::Shopping => {
+                self.shop_systems
+                    .execute(&mut self.ecs, &mut self.resources);
+            }
+            TurnState::Targeting => {
+                self.targeting_systems
+                    .execute(&mut self.ecs, &mut self.resources);
+            }
+            TurnState::Looking => {
+                self.look_systems
+                    .execute(&mut self.ecs, &mut self.resources);
+            }
+            TurnState::PlayerTurn => {
+                self.player_systems
+                    .execute(&mut self.ecs, &mut self.resources);
+            }
+            TurnState::MonsterTurn => {
+                self.monster_systems
+                    .execute(&mut self.ecs, &mut self.resources);
+            }
+            TurnState::NextLevel => self.advance_level(),
+            TurnState::PreviousLevel => self.retreat_level(),
+            _ => {}
+        }
+
+        // A run can't be continued once it's over, but it can make the high score table. Watching
+        // a replay leaves all of these alone
+        if self.turn_state().is_over() && self.replay.is_none() {
+            SaveGame::delete();
+            self.record_score();
+            self.write_morgue();
+        }
+    }
+
+    fn write_morgue(&mut self) {
+        let victory = self.turn_state() == TurnState::Victory;
+        self.morgue_file = match write_morgue(&self.ecs, &self.resources, &self.run, victory) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                eprintln!("Unable to write the morgue file: {}", e);
+                None
+            }
+        };
+    }
+
+    fn record_score(&mut self) {
+        let gold = <&Gold>::query()
+            .filter(component::<Player>())
+            .iter(&self.ecs)
+            .next()
+            .map_or(0, |gold| gold.0);
+        let score = Score::new(
+            self.run.seed,
+            self.run.difficulty.clone(),
+            &self.resources.get::<RunStats>().unwrap(),
+            gold,
+            self.turn_state() == TurnState::Victory,
+        );
+        self.last_rank = self.high_scores.add(score);
+        self.high_scores.save();
+    }
+
+    fn main_menu(&mut self, ctx: &mut BTerm) {
+        ctx.set_active_console(2);
+        ctx.print_color_centered(20, YELLOW, BLACK, "Dungeon Crawler");
+        ctx.print_color_centered(
+            22,
+            WHITE,
+            BLACK,
+            "Descend into the dungeon and claim the Amulet of Yala.",
+        );
+
+        let items = [
+            MenuItem::new("New Game"),
+            if SaveGame::exists() {
+                MenuItem::new("Continue")
+            } else {
+                MenuItem::disabled("Continue")
+            },
+            MenuItem::new("High Scores"),
+            MenuItem::new("Watch a Replay"),
+            MenuItem::new("Options"),
+            MenuItem::new("Quit"),
+        ];
+        match menu(ctx, 30, &items, &mut self.menu_selection) {
+            Some(0) => self.set_turn_state(TurnState::NewGame),
+            Some(1) => self.continue_game(ctx),
+            Some(2) => {
+                self.last_rank = None;
+                self.set_turn_state(TurnState::HighScores);
+            }
+            Some(3) => self.set_turn_state(TurnState::ChoosingReplay),
+            Some(4) => self.set_turn_state(TurnState::Options),
+            Some(5) => ctx.quit(),
+            _ => {}
+        }
+    }
+
+    fn new_game_menu(&mut self, ctx: &mut BTerm) {
+        ctx.set_active_console(2);
+        ctx.print_color_centered(20, YELLOW, BLACK, "New Game");
+        ctx.print_color_centered(
+            22,
+            WHITE,
+            BLACK,
+            "Type a seed to play a particular dungeon, or leave it empty for a random one.",
+        );
+
+        // Type in the seed
+        match ctx.key {
+            Some(VirtualKeyCode::Back) => {
+                self.seed_entry.pop();
+            }
+            Some(key) => {
+                let digit = match key {
+                    VirtualKeyCode::Key0 => Some('0'),
+                    VirtualKeyCode::Key1 => Some('1'),
+                    VirtualKeyCode::Key2 => Some('2'),
+                    VirtualKeyCode::Key3 => Some('3'),
+                    VirtualKeyCode::Key4 => Some('4'),
+                    VirtualKeyCode::Key5 => Some('5'),
+                    VirtualKeyCode::Key6 => Some('6'),
+                    VirtualKeyCode::Key7 => Some('7'),
+                    VirtualKeyCode::Key8 => Some('8'),
+                    VirtualKeyCode::Key9 => Some('9'),
+                    _ => None,
+                };
+                if let Some(digit) = digit {
+                    if self.seed_entry.len() < 18 {
+                        self.seed_entry.push(digit);
+                    }
+                }
+            }
+            None => {}
+        }
+
+        // Change the difficulty, class or goal with the left and right cursor keys while it's
+        // selected
+        match (self.menu_selection, ctx.key) {
+            (0, Some(VirtualKeyCode::Left)) => self.previous_difficulty(),
+            (0, Some(VirtualKeyCode::Right)) => self.next_difficulty(),
+            (1, Some(VirtualKeyCode::Left)) => self.previous_class(),
+            (1, Some(VirtualKeyCode::Right)) => self.next_class(),
+            (2, Some(VirtualKeyCode::Left | VirtualKeyCode::Right)) => self.goal = self.goal.next(),
+            _ => {}
+        }
+
+        // Describe the class being played
+        ctx.print_color_centered(24, GREY, BLACK, &self.classes[self.class].description);
+
+        let seed = if self.seed_entry.is_empty() {
+            "Random".to_string()
+        } else {
+            self.seed_entry.clone()
+        };
+        let items = [
+            MenuItem::new(format!(
+                "Difficulty: {}",
+                self.difficulties[self.difficulty].name
+            )),
+            MenuItem::new(format!("Class: {}", self.classes[self.class].name)),
+            MenuItem::new(format!("Goal: {}", self.goal.name())),
+            MenuItem::new(format!("Seed: {}", seed)),
+            MenuItem::new("Start"),
+            MenuItem::new("Back"),
+        ];
+        match menu(ctx, 30, &items, &mut self.menu_selection) {
+            Some(0) => self.next_difficulty(),
+            Some(1) => self.next_class(),
+            Some(2) => self.goal = self.goal.next(),
+            Some(4) => {
+                let seed = self
+                    .seed_entry
+                    .parse()
+                    .unwrap_or_else(|_| RandomNumberGenerator::new().next_u64());
+                self.start_game(SaveGame::new(
+                    seed,
+                    self.difficulties[self.difficulty].clone(),
+                    self.goal,
+                    self.classes[self.class].clone(),
+                    self.options.theme,
+                ));
+            }
+            Some(5) => self.set_turn_state(TurnState::MainMenu),
+            _ => {
+                if let Some(VirtualKeyCode::Escape) = ctx.key {
+                    self.set_turn_state(TurnState::MainMenu);
+                }
+            }
+        }
+    }
+
+    // Selects the next difficulty preset, wrapping around from the last to the first
+    fn next_difficulty(&mut self) {
+        self.difficulty = (self.difficulty + 1) % self.difficulties.len();
+    }
+
+    // Selects the previous difficulty preset, wrapping around from the first to the last
+    fn previous_difficulty(&mut self) {
+        self.difficulty = (self.difficulty + self.difficulties.len() - 1) % self.difficulties.len();
+    }
+
+    // Selects the next player class, wrapping around from the last to the first
+    fn next_class(&mut self) {
+        self.class = (self.class + 1) % self.classes.len();
+    }
+
+    // Selects the previous player class, wrapping around from the first to the last
+    fn previous_class(&mut self) {
+        self.class = (self.class + self.classes.len() - 1) % self.classes.len();
+    }
+
+    fn options_menu(&mut self, ctx: &mut BTerm) {
+        ctx.set_active_console(2);
+        ctx.print_color_centered(20, YELLOW, BLACK, "Options");
+
+        let items = [
+            MenuItem::new(format!("Movement: {}", self.options.movement_keys.name())),
+            MenuItem::new(format!("Theme: {}", self.options.theme.name())),
+            MenuItem::new(format!("Camera: {}", self.options.camera.name())),
+            MenuItem::new("Back"),
+        ];
+        match menu(ctx, 30, &items, &mut self.menu_selection) {
+            Some(0) => {
+                self.options.movement_keys = self.options.movement_keys.next();
+                self.options.save();
+            }
+            Some(1) => {
+                self.options.theme = self.options.theme.next();
+                self.options.save();
+            }
+            Some(2) => {
+                self.options.camera = self.options.camera.next();
+                self.options.save();
+            }
+            Some(3) => self.set_turn_state(TurnState::MainMenu),
+            _ => {
+                if let Some(VirtualKeyCode::Escape) = ctx.key {
+                    self.set_turn_state(TurnState::MainMenu);
+                }
+            }
+        }
+    }
+
+    fn pause_menu(&mut self, ctx: &mut BTerm) {
+        ctx.set_active_console(2);
+        ctx.print_color_centered(20, YELLOW, BLACK, "Paused");
+
+        let items = [
+            MenuItem::new("Resume"),
+            MenuItem::new("Save and Quit"),
+            MenuItem::new("Abandon Run"),
+            MenuItem::new("Controls"),
+            MenuItem::new("Message History"),
+        ];
+        match menu(ctx, 30, &items, &mut self.menu_selection) {
+            Some(0) => self.set_turn_state(TurnState::AwaitingInput),
+            Some(1) => {
+                self.run.save();
+                self.set_turn_state(TurnState::MainMenu);
+            }
+            Some(2) => {
+                SaveGame::delete();
+                self.set_turn_state(TurnState::MainMenu);
+            }
+            Some(3) => self.set_turn_state(TurnState::ShowingControls),
+            Some(4) => {
+                self.message_scroll = 0;
+                self.set_turn_state(TurnState::ShowingMessages);
+            }
+            _ => {
+                if let Some(VirtualKeyCode::Escape) = ctx.key {
+                    self.set_turn_state(TurnState::AwaitingInput);
+                }
+            }
+        }
+    }
+
+    fn controls(&mut self, ctx: &mut BTerm) {
+        ctx.set_active_console(2);
+        ctx.print_color_centered(20, YELLOW, BLACK, "Controls");
+
+        let controls = [
+            ("Cursor keys", "Move, or attack a monster in the way"),
+            ("G", "Pick up the items you're standing on"),
+            ("S", "Search for hidden traps nearby"),
+            ("1-9", "Use an item you're carrying"),
+            ("I", "Open the inventory to equip or use items"),
+            ("C", "Open the character sheet"),
+            ("M", "Show or hide the minimap"),
+            ("L", "Look around the map"),
+            ("F", "Aim a firebolt"),
+            ("B", "Aim a blink"),
+            ("H", "Cast heal"),
+            ("Space", "Rest for a turn, recovering health and mana"),
+            ("Escape", "Open this menu"),
+        ];
+        controls.iter().enumerate().for_each(|(i, (key, action))| {
+            let y = 24 + i as i32 * 2;
+            ctx.print_color_right(SCREEN_WIDTH - 2, y, YELLOW, BLACK, key);
+            ctx.print_color(SCREEN_WIDTH + 2, y, WHITE, BLACK, action);
+        });
+
+        let items = [MenuItem::new("Back")];
+        let back = menu(
+            ctx,
+            26 + controls.len() as i32 * 2,
+            &items,
+            &mut self.menu_selection,
+        );
+        if back.is_some() || ctx.key == Some(VirtualKeyCode::Escape) {
+            self.set_turn_state(TurnState::Paused);
+        }
+    }
+
+    fn message_history(&mut self, ctx: &mut BTerm) {
+        const PAGE_SIZE: usize = 80;
+
+        ctx.set_active_console(2);
+        ctx.print_color_centered(2, YELLOW, BLACK, "Message History");
+
+        // Scroll back through the log with the cursor keys
+        let log = self.resources.get::<GameLog>().unwrap();
+        let max_scroll = log.entries.len().saturating_sub(PAGE_SIZE);
+        match ctx.key {
+            Some(VirtualKeyCode::Up) => self.message_scroll += 1,
+            Some(VirtualKeyCode::Down) => {
+                self.message_scroll = self.message_scroll.saturating_sub(1)
+            }
+            Some(VirtualKeyCode::PageUp) => self.message_scroll += PAGE_SIZE,
+            Some(VirtualKeyCode::PageDown) => {
+                self.message_scroll = self.message_scroll.saturating_sub(PAGE_SIZE)
+            }
+            _ => {}
+        }
+        self.message_scroll = usize::min(self.message_scroll, max_scroll);
+
+        // Draw a page of the log, ending with the most recent message unless scrolled back
+        let messages = log.recent(PAGE_SIZE + self.message_scroll);
+        if messages.is_empty() {
+            ctx.print_color_centered(6, GREY, BLACK, "Nothing has happened yet.");
+        }
+        messages
+            .iter()
+            .take(PAGE_SIZE)
+            .enumerate()
+            .for_each(|(i, message)| ctx.print(4, 6 + i as i32, message));
+        ctx.print_color_centered(
+            SCREEN_HEIGHT * 2 - 3,
+            YELLOW,
+            BLACK,
+            "Cursor keys or Page Up/Down to scroll. Escape to go back.",
+        );
+        drop(log);
+
+        if ctx.key == Some(VirtualKeyCode::Escape) {
+            self.set_turn_state(TurnState::Paused);
         }
     }
 
     fn game_over(&mut self, ctx: &mut BTerm) {
         ctx.set_active_console(2);
         ctx.print_color_centered(2, RED, BLACK, "Your quest has ended.");
+        let cause = self
+            .resources
+            .get::<RunStats>()
+            .and_then(|stats| stats.killed_by.clone())
+            .unwrap_or_else(|| "something".to_string());
         ctx.print_color_centered(
             4,
             WHITE,
             BLACK,
-            "Slain by a monster, your hero's journey has come to a premature end.",
+            format!(
+                "Killed by {}, your hero's journey has come to a premature end.",
+                cause
+            ),
         );
         ctx.print_color_centered(
             5,
@@ -100,43 +912,18 @@ impl State {
             "Don't worry, you can always try again with a new hero.",
         );
         ctx.print_color_centered(9, GREEN, BLACK, "Press 1 to play again.");
-
-        if let Some(VirtualKeyCode::Key1) = ctx.key {
-            self.reset_game_state();
+        ctx.print_color_centered(10, GREEN, BLACK, "Press Escape to return to the main menu.");
+        if let Some(path) = &self.morgue_file {
+            ctx.print_color_centered(
+                12,
+                GREY,
+                BLACK,
+                format!("A morgue file was written to {}", path.display()),
+            );
         }
-    }
-
-    fn reset_game_state(&mut self) {
-        // Create a new world
-        self.ecs = World::default();
+        self.draw_high_scores(ctx, 20);
 
-        // Create a new resource manager
-        self.resources = Resources::default();
-
-        // Builder a new map
-        let mut rng = RandomNumberGenerator::new();
-        let map_builder = MapBuilder::new(&mut rng);
-
-        // Spawn the player
-        spawn_player(&mut self.ecs, map_builder.player_start);
-
-        // Spawn the amulet
-        spawn_amulet_of_yala(&mut self.ecs, map_builder.amulet_start);
-
-        // Spawn the monsters
-        map_builder
-            .monster_spawns
-            .iter()
-            .for_each(|pos| spawn_monster(&mut self.ecs, &mut rng, *pos));
-
-        // Add the map, viewpoint, and turn state to the resource manager
-        self.resources.insert(map_builder.map);
-        self.resources
-            .insert(Viewport::new(map_builder.player_start));
-        self.resources.insert(TurnState::AwaitingInput);
-
-        // Add the theme to the resources
-        self.resources.insert(map_builder.theme);
+        self.play_again(ctx);
     }
 
     fn victory(&mut self, ctx: &mut BTerm) {
@@ -146,7 +933,15 @@ impl State {
             4,
             WHITE,
             BLACK,
-            "You put on the Amulet of Yala and feel its power course through your veins.",
+            if self
+                .resources
+                .get::<RunStats>()
+                .is_some_and(|stats| stats.returning)
+            {
+                "You carry the Amulet of Yala out of the dungeon and into the daylight."
+            } else {
+                "You put on the Amulet of Yala and feel its power course through your veins."
+            },
         );
         ctx.print_color_centered(
             5,
@@ -155,54 +950,168 @@ impl State {
             "Your town is saved, and you can return to your normal life.",
         );
         ctx.print_color_centered(9, GREEN, BLACK, "Press 1 to play again.");
+        ctx.print_color_centered(10, GREEN, BLACK, "Press Escape to return to the main menu.");
+        if let Some(path) = &self.morgue_file {
+            ctx.print_color_centered(
+                12,
+                GREY,
+                BLACK,
+                format!("A morgue file was written to {}", path.display()),
+            );
+        }
+        self.draw_high_scores(ctx, 20);
+
+        self.play_again(ctx);
+    }
+
+    fn replay_menu(&mut self, ctx: &mut BTerm) {
+        ctx.set_active_console(2);
+        ctx.print_color_centered(20, YELLOW, BLACK, "Watch a Replay");
 
-        if let Some(VirtualKeyCode::Key1) = ctx.key {
-            self.reset_game_state();
+        // Offer the most recent replays, named after their seed and when they were recorded
+        let replays: Vec<PathBuf> = SaveGame::replays().into_iter().take(9).collect();
+        if replays.is_empty() {
+            ctx.print_color_centered(22, GREY, BLACK, "No runs have been recorded yet.");
+        }
+        let mut items: Vec<MenuItem> = replays
+            .iter()
+            .map(|path| {
+                MenuItem::new(
+                    path.file_stem()
+                        .map_or(String::new(), |stem| stem.to_string_lossy().to_string()),
+                )
+            })
+            .collect();
+        items.push(MenuItem::new("Back"));
+        match menu(ctx, 26, &items, &mut self.menu_selection) {
+            Some(n) if n < replays.len() => self.start_replay(&replays[n]),
+            Some(_) => self.set_turn_state(TurnState::MainMenu),
+            None => {
+                if let Some(VirtualKeyCode::Escape) = ctx.key {
+                    self.set_turn_state(TurnState::MainMenu);
+                }
+            }
+        }
+    }
+
+    fn high_scores(&mut self, ctx: &mut BTerm) {
+        ctx.set_active_console(2);
+        ctx.print_color_centered(20, YELLOW, BLACK, "High Scores");
+        self.draw_high_scores(ctx, 24);
+        ctx.print_color_centered(
+            SCREEN_HEIGHT * 2 - 3,
+            YELLOW,
+            BLACK,
+            "Press Escape to return to the main menu.",
+        );
+
+        if let Some(VirtualKeyCode::Escape) | Some(VirtualKeyCode::Return) = ctx.key {
+            self.set_turn_state(TurnState::MainMenu);
+        }
+    }
+
+    // Draws the high score table, highlighting the run that has just ended if it made the table
+    fn draw_high_scores(&self, ctx: &mut BTerm, top: i32) {
+        if self.high_scores.scores.is_empty() {
+            ctx.print_color_centered(top, GREY, BLACK, "No runs have been recorded yet.");
+            return;
+        }
+
+        ctx.print_color_centered(
+            top,
+            YELLOW,
+            BLACK,
+            format!(
+                "{:>4}  {:>6}  {:<6}  {:>5}  {:>6}  {:>5}  {:<20}  {:<30}",
+                "Rank", "Score", "Mode", "Depth", "Turns", "Kills", "Seed", "Fate"
+            ),
+        );
+        self.high_scores
+            .scores
+            .iter()
+            .enumerate()
+            .for_each(|(i, score)| {
+                let color = if Some(i) == self.last_rank {
+                    GREEN
+                } else {
+                    WHITE
+                };
+                ctx.print_color_centered(
+                    top + 2 + i as i32,
+                    color,
+                    BLACK,
+                    format!(
+                        "{:>4}  {:>6}  {:<6}  {:>5}  {:>6}  {:>5}  {:<20}  {:<30}",
+                        i + 1,
+                        score.score,
+                        score.difficulty.name,
+                        score.depth,
+                        score.turns,
+                        score.kills,
+                        score.seed,
+                        score.fate
+                    ),
+                );
+            });
+    }
+
+    // Starts a new run in a fresh dungeon at the same difficulty, or returns to the main menu
+    fn play_again(&mut self, ctx: &mut BTerm) {
+        match ctx.key {
+            Some(VirtualKeyCode::Key1) => self.start_game(SaveGame::new(
+                RandomNumberGenerator::new().next_u64(),
+                self.run.difficulty.clone(),
+                self.run.goal,
+                self.run.class.clone(),
+                self.options.theme,
+            )),
+            Some(VirtualKeyCode::Escape) => self.set_turn_state(TurnState::MainMenu),
+            _ => {}
         }
     }
 }
 
 impl GameState for State {
     fn tick(&mut self, ctx: &mut BTerm) {
-        // Clear the background console
-        ctx.set_active_console(0);
-        ctx.cls();
-
-        // Clear the foreground console
-        ctx.set_active_console(1);
-        ctx.cls();
-
-        // Clear the HUD console
-        ctx.set_active_console(2);
-        ctx.cls();
+        // Clear the background, foreground and HUD consoles
+        clear_consoles(ctx);
 
-        // Add any pressed key into the resources
-        self.resources.insert(ctx.key);
+        // Get any pressed key, translated according to the key bindings
+        let key = self.options.translate(ctx.key);
 
         // Render the mouse coordinates
         ctx.set_active_console(0);
         self.resources.insert(Point::from_tuple(ctx.mouse_pos()));
 
         // Execute the appropriate system, depending on the current turn state
-        let current_state = *self.resources.get::<TurnState>().unwrap();
+        let current_state = self.turn_state();
         match current_state {
-            TurnState::AwaitingInput => {
-                self.input_systems
-                    .execute(&mut self.ecs, &mut self.resources);
-            }
-            TurnState::PlayerTurn => {
-                self.player_systems
-                    .execute(&mut self.ecs, &mut self.resources);
-            }
-            TurnState::MonsterTurn => {
-                self.monster_systems
-                    .execute(&mut self.ecs, &mut self.resources);
-            }
-            TurnState::GameOver => {
-                self.game_over(ctx);
+            TurnState::MainMenu => self.main_menu(ctx),
+            TurnState::NewGame => self.new_game_menu(ctx),
+            TurnState::Options => self.options_menu(ctx),
+            TurnState::HighScores => self.high_scores(ctx),
+            TurnState::GameOver => self.game_over(ctx),
+            TurnState::Victory => self.victory(ctx),
+            TurnState::Paused => self.pause_menu(ctx),
+            TurnState::ShowingControls => self.controls(ctx),
+            TurnState::ShowingMessages => self.message_history(ctx),
+            TurnState::ChoosingReplay => self.replay_menu(ctx),
+            _ if self.replay.is_some() => self.replay_tick(ctx),
+            TurnState::AwaitingInput if key == Some(VirtualKeyCode::Escape) => {
+                // Pause the game, leaving the turn schedules alone until it is resumed
+                self.set_turn_state(TurnState::Paused);
             }
-            TurnState::Victory => {
-                self.victory(ctx);
+            _ => {
+                // Record the keys the player presses, so that the run can be replayed. The
+                // recording is written out as each key arrives, so that it survives a crash
+                if let Some(key) = key {
+                    if current_state.is_awaiting_player() {
+                        let turn = self.resources.get::<RunStats>().unwrap().turns;
+                        self.run.inputs.push(RecordedInput { turn, key });
+                        self.run.save_replay();
+                    }
+                }
+                self.execute(key);
             }
         }
 
@@ -211,6 +1120,21 @@ impl GameState for State {
     }
 }
 
+// Clears the background, foreground and HUD consoles
+fn clear_consoles(ctx: &mut BTerm) {
+    for console in 0..=2 {
+        ctx.set_active_console(console);
+        ctx.cls();
+    }
+}
+
+// Renders the draw operations queued up so far, and clears them off the consoles again. This stops
+// operations from piling up while several turns are played out at once
+fn flush_draw_buffer(ctx: &mut BTerm) {
+    render_draw_buffer(ctx).expect("Render error");
+    clear_consoles(ctx);
+}
+
 fn main() -> BError {
     // Create a new terminal context
     let context = BTermBuilder::new()
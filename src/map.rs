@@ -2,15 +2,21 @@ use crate::prelude::*;
 
 const NUM_TILES: usize = (SCREEN_WIDTH * SCREEN_HEIGHT) as usize;
 
+// The cost of stepping onto a discovered trap, high enough that paths go around one where they can
+const KNOWN_TRAP_COST: f32 = 20.0;
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum TileType {
     Wall,
     Floor,
+    Exit,
+    UpStairs,
 }
 
 pub struct Map {
     pub tiles: Vec<TileType>,
     pub revealed_tiles: Vec<bool>,
+    pub known_traps: Vec<bool>,
 }
 
 pub fn map_idx(x: i32, y: i32) -> usize {
@@ -22,6 +28,7 @@ impl Map {
         Self {
             tiles: vec![TileType::Floor; NUM_TILES],
             revealed_tiles: vec![false; NUM_TILES],
+            known_traps: vec![false; NUM_TILES],
         }
     }
 
@@ -30,7 +37,7 @@ impl Map {
     }
 
     pub fn can_enter_tile(&self, point: Point) -> bool {
-        self.in_bounds(point) && self.tiles[map_idx(point.x, point.y)] == TileType::Floor
+        self.in_bounds(point) && self.tiles[map_idx(point.x, point.y)] != TileType::Wall
     }
 
     pub fn try_idx(&self, point: Point) -> Option<usize> {
@@ -54,6 +61,40 @@ impl Map {
             None
         }
     }
+
+    // Finds the exits from a tile, steering paths around traps that have been discovered unless
+    // asked to ignore them
+    fn exits(&self, idx: usize, avoid_traps: bool) -> SmallVec<[(usize, f32); 10]> {
+        let mut exits = SmallVec::new();
+        let location = self.index_to_point2d(idx);
+        [
+            Point::new(-1, 0),
+            Point::new(1, 0),
+            Point::new(0, -1),
+            Point::new(0, 1),
+        ]
+        .iter()
+        .filter_map(|delta| self.valid_exit(location, *delta))
+        .for_each(|idx| {
+            let cost = if avoid_traps && self.known_traps[idx] {
+                KNOWN_TRAP_COST
+            } else {
+                1.0
+            };
+            exits.push((idx, cost));
+        });
+        exits
+    }
+}
+
+// A view of the map for things that spread through the dungeon without caring where the traps are,
+// such as sounds and alerts
+pub struct Acoustics<'a>(pub &'a Map);
+
+impl BaseMap for Acoustics<'_> {
+    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
+        self.0.exits(idx, false)
+    }
 }
 
 impl Algorithm2D for Map {
@@ -72,21 +113,7 @@ impl BaseMap for Map {
     ///
     /// * idx: the map index of the tile
     fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
-        let mut exits = SmallVec::new();
-        let location = self.index_to_point2d(idx);
-        if let Some(idx) = self.valid_exit(location, Point::new(-1, 0)) {
-            exits.push((idx, 1.0));
-        }
-        if let Some(idx) = self.valid_exit(location, Point::new(1, 0)) {
-            exits.push((idx, 1.0));
-        }
-        if let Some(idx) = self.valid_exit(location, Point::new(0, -1)) {
-            exits.push((idx, 1.0));
-        }
-        if let Some(idx) = self.valid_exit(location, Point::new(0, 1)) {
-            exits.push((idx, 1.0));
-        }
-        exits
+        self.exits(idx, true)
     }
 
     ///
@@ -103,6 +130,6 @@ impl BaseMap for Map {
     ///
     /// * idx: the map index of the tile
     fn is_opaque(&self, idx: usize) -> bool {
-        self.tiles[idx as usize] != TileType::Floor
+        self.tiles[idx] == TileType::Wall
     }
 }
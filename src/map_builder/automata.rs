@@ -7,11 +7,14 @@ impl MapArchitect for CellularAutomataArchitect {
     ///
     /// Creates a new instance
     /// * `rng` - a RandomNumberGenerator
-    fn new(&mut self, rng: &mut RandomNumberGenerator) -> MapBuilder {
+    fn new(&mut self, rng: &mut RandomNumberGenerator, num_monsters: usize) -> MapBuilder {
         let mut mb = MapBuilder {
             map: Map::new(),
             rooms: Vec::new(),
             monster_spawns: Vec::new(),
+            war_band_spawns: Vec::new(),
+            trap_spawns: Vec::new(),
+            shopkeeper_start: None,
             player_start: Point::zero(),
             amulet_start: Point::zero(),
             theme: super::themes::DungeonTheme::new(),
@@ -21,7 +24,7 @@ impl MapArchitect for CellularAutomataArchitect {
             self.iteration(&mut mb.map);
         }
         let start = self.find_start(&mb.map);
-        mb.monster_spawns = mb.spawn_monsters(&start, rng);
+        mb.monster_spawns = mb.spawn_monsters(&start, rng, num_monsters);
         mb.player_start = start;
         mb.amulet_start = mb.find_most_distant(mb.player_start);
         mb
@@ -77,7 +80,7 @@ impl CellularAutomataArchitect {
                     DistanceAlg::Pythagoras.distance2d(center, map.index_to_point2d(idx)),
                 )
             })
-            .min_by(|(_, distance), (_, distance2)| distance.partial_cmp(&distance2).unwrap())
+            .min_by(|(_, distance), (_, distance2)| distance.partial_cmp(distance2).unwrap())
             .map(|(idx, _)| idx)
             .unwrap();
         map.index_to_point2d(closest_point)
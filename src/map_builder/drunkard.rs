@@ -9,7 +9,7 @@ pub struct DrunkardsWalkArchitect {}
 
 impl DrunkardsWalkArchitect {
     fn drunkard(&mut self, start: &Point, rng: &mut RandomNumberGenerator, map: &mut Map) {
-        let mut drunkard_pos = start.clone();
+        let mut drunkard_pos = *start;
         let mut distance_staggered = 0;
 
         loop {
@@ -35,12 +35,15 @@ impl DrunkardsWalkArchitect {
 }
 
 impl MapArchitect for DrunkardsWalkArchitect {
-    fn new(&mut self, rng: &mut RandomNumberGenerator) -> MapBuilder {
+    fn new(&mut self, rng: &mut RandomNumberGenerator, num_monsters: usize) -> MapBuilder {
         // Create an empty MapBuilder
         let mut mb = MapBuilder {
             map: Map::new(),
             rooms: Vec::new(),
             monster_spawns: Vec::new(),
+            war_band_spawns: Vec::new(),
+            trap_spawns: Vec::new(),
+            shopkeeper_start: None,
             player_start: Point::zero(),
             amulet_start: Point::zero(),
             theme: super::themes::DungeonTheme::new(),
@@ -72,7 +75,7 @@ impl MapArchitect for DrunkardsWalkArchitect {
             let dijkstra_map = DijkstraMap::new(
                 SCREEN_WIDTH,
                 SCREEN_HEIGHT,
-                &vec![mb.map.point2d_to_index(center)],
+                &[mb.map.point2d_to_index(center)],
                 &mb.map,
                 1024.0,
             );
@@ -84,7 +87,7 @@ impl MapArchitect for DrunkardsWalkArchitect {
                 .for_each(|(idx, _)| mb.map.tiles[idx] = TileType::Wall);
         }
 
-        mb.monster_spawns = mb.spawn_monsters(&center, rng);
+        mb.monster_spawns = mb.spawn_monsters(&center, rng, num_monsters);
         mb.player_start = center;
         mb.amulet_start = mb.find_most_distant(mb.player_start);
 
@@ -1,14 +1,18 @@
 use super::MapArchitect;
 use crate::prelude::*;
 
+#[allow(dead_code)]
 pub struct EmptyArchitect {}
 
 impl MapArchitect for EmptyArchitect {
-    fn new(&mut self, rng: &mut RandomNumberGenerator) -> MapBuilder {
+    fn new(&mut self, rng: &mut RandomNumberGenerator, num_monsters: usize) -> MapBuilder {
         let mut mb = MapBuilder {
             map: Map::new(),
             rooms: Vec::new(),
             monster_spawns: Vec::new(),
+            war_band_spawns: Vec::new(),
+            trap_spawns: Vec::new(),
+            shopkeeper_start: None,
             player_start: Point::zero(),
             amulet_start: Point::zero(),
             theme: super::themes::DungeonTheme::new(),
@@ -16,7 +20,7 @@ impl MapArchitect for EmptyArchitect {
         mb.fill(TileType::Floor);
         mb.player_start = Point::new(SCREEN_WIDTH / 2, SCREEN_HEIGHT / 2);
         mb.amulet_start = mb.find_most_distant(mb.player_start);
-        for _ in 0..50 {
+        for _ in 0..num_monsters {
             mb.monster_spawns.push(Point::new(
                 rng.range(1, SCREEN_WIDTH),
                 rng.range(1, SCREEN_HEIGHT),
@@ -1,10 +1,9 @@
 use crate::map_builder::automata::CellularAutomataArchitect;
 use crate::map_builder::drunkard::DrunkardsWalkArchitect;
-use crate::map_builder::prefab::apply_prefab;
+use crate::map_builder::prefab::{apply_prefab, FORTRESS, SHOP};
 use crate::map_builder::rooms::RoomsArchitect;
 use crate::prelude::*;
-use empty::EmptyArchitect;
-use themes::*;
+pub use themes::*;
 
 mod automata;
 mod drunkard;
@@ -14,6 +13,7 @@ mod rooms;
 mod themes;
 
 const NUM_ROOMS: usize = 20;
+const NUM_TRAPS: usize = 15;
 
 ///
 /// A struct that defines the information required to build out a game map
@@ -22,6 +22,9 @@ pub struct MapBuilder {
     pub map: Map,
     pub rooms: Vec<Rect>,
     pub monster_spawns: Vec<Point>,
+    pub war_band_spawns: Vec<Point>,
+    pub trap_spawns: Vec<Point>,
+    pub shopkeeper_start: Option<Point>,
     pub player_start: Point,
     pub amulet_start: Point,
     pub theme: Box<dyn MapTheme>,
@@ -30,8 +33,9 @@ pub struct MapBuilder {
 ///
 /// A trait that defines a mechanism that architects maps according to an algorithm
 ///
+#[allow(clippy::new_ret_no_self, clippy::wrong_self_convention)]
 trait MapArchitect {
-    fn new(&mut self, rng: &mut RandomNumberGenerator) -> MapBuilder;
+    fn new(&mut self, rng: &mut RandomNumberGenerator, num_monsters: usize) -> MapBuilder;
 }
 
 ///
@@ -39,6 +43,8 @@ trait MapArchitect {
 ///
 pub trait MapTheme: Sync + Send {
     fn tile_to_render(&self, tile_type: TileType) -> FontCharType;
+    fn describe(&self, tile_type: TileType) -> &'static str;
+    fn monsters(&self, depth: i32) -> SpawnTable;
 }
 
 ///
@@ -121,7 +127,7 @@ impl MapBuilder {
         let mut rooms = self.rooms.clone();
 
         // Sort the rooms by their center X coordinates
-        rooms.sort_by(|a, b| a.center().x.cmp(&b.center().x));
+        rooms.sort_by_key(|a| a.center().x);
 
         // Iterate through the rooms, skipping every other, and build tunnels between them
         for (i, room) in rooms.iter().enumerate().skip(1) {
@@ -146,7 +152,7 @@ impl MapBuilder {
         use std::cmp::{max, min};
         for y in min(y1, y2)..=max(y1, y2) {
             if let Some(idx) = self.map.try_idx(Point::new(x, y)) {
-                self.map.tiles[idx as usize] = TileType::Floor;
+                self.map.tiles[idx] = TileType::Floor;
             }
         }
     }
@@ -160,7 +166,7 @@ impl MapBuilder {
         use std::cmp::{max, min};
         for x in min(x1, x2)..=max(x1, x2) {
             if let Some(idx) = self.map.try_idx(Point::new(x, y)) {
-                self.map.tiles[idx as usize] = TileType::Floor;
+                self.map.tiles[idx] = TileType::Floor;
             }
         }
     }
@@ -168,7 +174,8 @@ impl MapBuilder {
     ///
     /// Creates a new instance
     /// * `rng` - a RandomNumberGenerator
-    pub fn new(rng: &mut RandomNumberGenerator) -> Self {
+    /// * `num_monsters` - the number of monsters and items to spawn
+    pub fn new(rng: &mut RandomNumberGenerator, num_monsters: usize) -> Self {
         //
         // Randomly select the architect
         let mut architect: Box<dyn MapArchitect> = match rng.range(0, 3) {
@@ -178,10 +185,19 @@ impl MapBuilder {
         };
 
         // Use the architect to build the map
-        let mut mb = architect.new(rng);
+        let mut mb = architect.new(rng, num_monsters);
 
-        // Apply a prefab fortress
-        apply_prefab(&mut mb, rng);
+        // Apply a prefab fortress, and occasionally a shop
+        let mut taken = Vec::new();
+        if let Some(fortress) = apply_prefab(&mut mb, rng, FORTRESS, &taken) {
+            taken.push(fortress);
+        }
+        if rng.range(0, 3) == 0 {
+            apply_prefab(&mut mb, rng, SHOP, &taken);
+        }
+
+        // Hide traps around the map
+        mb.trap_spawns = mb.spawn_traps(rng);
 
         // Randomly select the theme for the map
         mb.theme = match rng.range(0, 2) {
@@ -196,9 +212,13 @@ impl MapBuilder {
     /// Spawns monsters from the specified start point
     /// * `start` - the start point
     /// * `rng` - a RandomNumberGenerator
-    fn spawn_monsters(&self, start_point: &Point, rng: &mut RandomNumberGenerator) -> Vec<Point> {
-        const NUM_MONSTERS: usize = 50;
-
+    /// * `num_monsters` - the number of monsters to spawn, as far as there's room for them
+    fn spawn_monsters(
+        &self,
+        start_point: &Point,
+        rng: &mut RandomNumberGenerator,
+        num_monsters: usize,
+    ) -> Vec<Point> {
         // Create the collection of tiles on which we can spawn monsters
         let mut spawnable_tiles: Vec<Point> = self
             .map
@@ -216,11 +236,41 @@ impl MapBuilder {
 
         // Create a new collection of monster spawn points
         let mut monster_spawns = Vec::new();
-        for _ in 0..NUM_MONSTERS {
+        for _ in 0..usize::min(num_monsters, spawnable_tiles.len()) {
             let target_index = rng.random_slice_index(&spawnable_tiles).unwrap();
             monster_spawns.push(spawnable_tiles[target_index]);
             spawnable_tiles.remove(target_index);
         }
         monster_spawns
     }
+
+    ///
+    /// Picks the spots for traps, away from the player's start and anything else placed on the map
+    /// * `rng` - a RandomNumberGenerator
+    fn spawn_traps(&self, rng: &mut RandomNumberGenerator) -> Vec<Point> {
+        // Create the collection of open floor tiles on which we can place traps
+        let mut trappable_tiles: Vec<Point> = self
+            .map
+            .tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| **t == TileType::Floor)
+            .map(|(idx, _)| self.map.index_to_point2d(idx))
+            .filter(|pt| {
+                DistanceAlg::Pythagoras.distance2d(self.player_start, *pt) > 5.0
+                    && *pt != self.amulet_start
+                    && Some(*pt) != self.shopkeeper_start
+                    && !self.monster_spawns.contains(pt)
+                    && !self.war_band_spawns.contains(pt)
+            })
+            .collect();
+
+        // Create a new collection of trap spawn points
+        let mut trap_spawns = Vec::new();
+        while trap_spawns.len() < NUM_TRAPS && !trappable_tiles.is_empty() {
+            let target_index = rng.random_slice_index(&trappable_tiles).unwrap();
+            trap_spawns.push(trappable_tiles.remove(target_index));
+        }
+        trap_spawns
+    }
 }
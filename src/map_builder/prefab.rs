@@ -1,6 +1,6 @@
 use crate::prelude::*;
 
-const FORTRESS: (&str, i32, i32) = (
+pub const FORTRESS: (&str, i32, i32) = (
     "
  	------------
  	---######---
@@ -18,12 +18,38 @@ const FORTRESS: (&str, i32, i32) = (
     11,
 );
 
-pub fn apply_prefab(mb: &mut MapBuilder, rng: &mut RandomNumberGenerator) {
+pub const SHOP: (&str, i32, i32) = (
+    "
+ 	----------
+ 	-########-
+ 	-#------#-
+ 	-#--V---#-
+ 	-#------#-
+ 	-###--###-
+ 	----------
+ 	",
+    10,
+    7,
+);
+
+///
+/// Applies a prefab to the map, somewhere away from the player and the amulet, and clear of the
+/// areas already taken by other prefabs. Returns the area taken by the prefab, if it was placed
+/// * `mb` - the MapBuilder
+/// * `rng` - a RandomNumberGenerator
+/// * `prefab` - the prefab's layout, width and height
+/// * `taken` - the areas already taken by other prefabs
+pub fn apply_prefab(
+    mb: &mut MapBuilder,
+    rng: &mut RandomNumberGenerator,
+    prefab: (&str, i32, i32),
+    taken: &[Rect],
+) -> Option<Rect> {
     let mut placement = None;
     let dijkstra_map = DijkstraMap::new(
         SCREEN_WIDTH,
         SCREEN_HEIGHT,
-        &vec![mb.map.point2d_to_index(mb.player_start)],
+        &[mb.map.point2d_to_index(mb.player_start)],
         &mb.map,
         1024.0,
     );
@@ -31,12 +57,16 @@ pub fn apply_prefab(mb: &mut MapBuilder, rng: &mut RandomNumberGenerator) {
     let mut attempts = 0;
     while placement.is_none() && attempts < 10 {
         let dimensions = Rect::with_size(
-            rng.range(0, SCREEN_WIDTH - FORTRESS.1),
-            rng.range(0, SCREEN_HEIGHT - FORTRESS.2),
-            FORTRESS.1,
-            FORTRESS.2,
+            rng.range(0, SCREEN_WIDTH - prefab.1),
+            rng.range(0, SCREEN_HEIGHT - prefab.2),
+            prefab.1,
+            prefab.2,
         );
 
+        let is_clear = !taken.iter().any(|area| area.intersect(&dimensions))
+            && !dimensions.point_in_rect(mb.amulet_start)
+            && !dimensions.point_in_rect(mb.player_start);
+
         let mut can_place = false;
         dimensions.for_each(|pt| {
             let idx = mb.map.point2d_to_index(pt);
@@ -46,8 +76,8 @@ pub fn apply_prefab(mb: &mut MapBuilder, rng: &mut RandomNumberGenerator) {
             }
         });
 
-        if can_place {
-            placement = Some(Point::new(dimensions.x1, dimensions.y1));
+        if is_clear && can_place {
+            placement = Some(dimensions);
             let points = dimensions.point_set();
             mb.monster_spawns.retain(|pt| !points.contains(pt));
         }
@@ -55,20 +85,20 @@ pub fn apply_prefab(mb: &mut MapBuilder, rng: &mut RandomNumberGenerator) {
     }
 
     if let Some(placement) = placement {
-        let string_vec: Vec<char> = FORTRESS
-            .0
-            .chars()
-            .filter(|a| *a != '\r' && *a != '\n')
-            .collect();
+        let string_vec: Vec<char> = prefab.0.chars().filter(|a| !a.is_whitespace()).collect();
         let mut i = 0;
-        for ty in placement.y..placement.y + FORTRESS.2 {
-            for tx in placement.x..placement.x + FORTRESS.1 {
+        for ty in placement.y1..placement.y1 + prefab.2 {
+            for tx in placement.x1..placement.x1 + prefab.1 {
                 let idx = map_idx(tx, ty);
                 let c = string_vec[i];
                 match c {
                     'M' => {
                         mb.map.tiles[idx] = TileType::Floor;
-                        mb.monster_spawns.push(Point::new(tx, ty));
+                        mb.war_band_spawns.push(Point::new(tx, ty));
+                    }
+                    'V' => {
+                        mb.map.tiles[idx] = TileType::Floor;
+                        mb.shopkeeper_start = Some(Point::new(tx, ty));
                     }
                     '-' => mb.map.tiles[idx] = TileType::Floor,
                     '#' => mb.map.tiles[idx] = TileType::Wall,
@@ -78,4 +108,6 @@ pub fn apply_prefab(mb: &mut MapBuilder, rng: &mut RandomNumberGenerator) {
             }
         }
     }
+
+    placement
 }
@@ -4,11 +4,14 @@ use crate::prelude::*;
 pub struct RoomsArchitect {}
 
 impl MapArchitect for RoomsArchitect {
-    fn new(&mut self, rng: &mut RandomNumberGenerator) -> MapBuilder {
+    fn new(&mut self, rng: &mut RandomNumberGenerator, num_monsters: usize) -> MapBuilder {
         let mut mb = MapBuilder {
             map: Map::new(),
             rooms: Vec::new(),
             monster_spawns: Vec::new(),
+            war_band_spawns: Vec::new(),
+            trap_spawns: Vec::new(),
+            shopkeeper_start: None,
             player_start: Point::zero(),
             amulet_start: Point::zero(),
             theme: super::themes::DungeonTheme::new(),
@@ -18,7 +21,7 @@ impl MapArchitect for RoomsArchitect {
         mb.build_corridors(rng);
         mb.player_start = mb.rooms[0].center();
         mb.amulet_start = mb.find_most_distant(mb.player_start);
-        for room in mb.rooms.iter().skip(1) {
+        for room in mb.rooms.iter().skip(1).take(num_monsters) {
             mb.monster_spawns.push(room.center());
         }
         mb
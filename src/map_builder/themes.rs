@@ -3,6 +3,7 @@ use crate::prelude::*;
 
 pub struct DungeonTheme {}
 
+#[allow(clippy::new_ret_no_self)]
 impl DungeonTheme {
     pub fn new() -> Box<dyn MapTheme> {
         Box::new(Self {})
@@ -14,12 +15,47 @@ impl MapTheme for DungeonTheme {
         match tile_type {
             TileType::Floor => to_cp437('.'),
             TileType::Wall => to_cp437('#'),
+            // The font only has the one staircase, so the stairs up share it
+            TileType::Exit | TileType::UpStairs => to_cp437('>'),
+        }
+    }
+
+    fn describe(&self, tile_type: TileType) -> &'static str {
+        match tile_type {
+            TileType::Floor => "Flagstone floor",
+            TileType::Wall => "Rough stone wall",
+            TileType::Exit => "Stairs leading down",
+            TileType::UpStairs => "Stairs leading up",
+        }
+    }
+
+    fn monsters(&self, depth: i32) -> SpawnTable {
+        match depth {
+            1 => SpawnTable::new(vec![
+                (6, MonsterKind::Goblin),
+                (2, MonsterKind::Orc),
+                (1, MonsterKind::Ettin),
+                (1, MonsterKind::Skeleton),
+            ]),
+            2 => SpawnTable::new(vec![
+                (4, MonsterKind::Goblin),
+                (3, MonsterKind::Orc),
+                (1, MonsterKind::Ettin),
+                (3, MonsterKind::Skeleton),
+            ]),
+            _ => SpawnTable::new(vec![
+                (2, MonsterKind::Goblin),
+                (4, MonsterKind::Orc),
+                (2, MonsterKind::Ettin),
+                (4, MonsterKind::Skeleton),
+            ]),
         }
     }
 }
 
 pub struct ForestTheme {}
 
+#[allow(clippy::new_ret_no_self)]
 impl ForestTheme {
     pub fn new() -> Box<dyn MapTheme> {
         Box::new(Self {})
@@ -31,6 +67,38 @@ impl MapTheme for ForestTheme {
         match tile_type {
             TileType::Floor => to_cp437(';'),
             TileType::Wall => to_cp437('"'),
+            TileType::Exit | TileType::UpStairs => to_cp437('>'),
+        }
+    }
+
+    fn describe(&self, tile_type: TileType) -> &'static str {
+        match tile_type {
+            TileType::Floor => "Mossy clearing",
+            TileType::Wall => "Dense thicket",
+            TileType::Exit => "A trail leading deeper into the woods",
+            TileType::UpStairs => "A trail leading out of the woods",
+        }
+    }
+
+    fn monsters(&self, depth: i32) -> SpawnTable {
+        match depth {
+            1 => SpawnTable::new(vec![
+                (5, MonsterKind::Goblin),
+                (4, MonsterKind::Wolf),
+                (1, MonsterKind::Orc),
+            ]),
+            2 => SpawnTable::new(vec![
+                (3, MonsterKind::Goblin),
+                (5, MonsterKind::Wolf),
+                (2, MonsterKind::Orc),
+                (1, MonsterKind::Ettin),
+            ]),
+            _ => SpawnTable::new(vec![
+                (1, MonsterKind::Goblin),
+                (5, MonsterKind::Wolf),
+                (3, MonsterKind::Orc),
+                (2, MonsterKind::Ettin),
+            ]),
         }
     }
 }
@@ -0,0 +1,78 @@
+use crate::prelude::*;
+
+// An entry in a menu, which can't be chosen while it's disabled
+pub struct MenuItem {
+    pub label: String,
+    pub enabled: bool,
+}
+
+impl MenuItem {
+    pub fn new<S: ToString>(label: S) -> Self {
+        Self {
+            label: label.to_string(),
+            enabled: true,
+        }
+    }
+
+    pub fn disabled<S: ToString>(label: S) -> Self {
+        Self {
+            label: label.to_string(),
+            enabled: false,
+        }
+    }
+}
+
+// Draws a menu on the HUD console, with its items centered on every other row from the top row, and
+// handles its input. The selection moves with the cursor keys or by hovering the mouse over an item,
+// and an item is chosen with Enter or a click. Returns the index of the chosen item, if any
+pub fn menu(ctx: &mut BTerm, top: i32, items: &[MenuItem], selection: &mut usize) -> Option<usize> {
+    ctx.set_active_console(2);
+
+    // Make sure an enabled item is selected
+    if *selection >= items.len() || !items[*selection].enabled {
+        *selection = items.iter().position(|item| item.enabled).unwrap_or(0);
+    }
+
+    // Move the selection with the cursor keys, skipping over disabled items
+    let step = match ctx.key {
+        Some(VirtualKeyCode::Up) => items.len() - 1,
+        Some(VirtualKeyCode::Down) => 1,
+        _ => 0,
+    };
+    if step != 0 {
+        let mut next = (*selection + step) % items.len();
+        while !items[next].enabled && next != *selection {
+            next = (next + step) % items.len();
+        }
+        *selection = next;
+    }
+
+    // Select the item under the mouse, choosing it if it's clicked
+    let mouse = ctx.mouse_point();
+    let mut chosen = None;
+    if let Some(i) = (0..items.len()).find(|i| top + *i as i32 * 2 == mouse.y) {
+        if items[i].enabled {
+            *selection = i;
+            if ctx.left_click {
+                chosen = Some(i);
+            }
+        }
+    }
+    if let Some(VirtualKeyCode::Return) | Some(VirtualKeyCode::Space) = ctx.key {
+        chosen = Some(*selection);
+    }
+
+    // Draw the items, highlighting the selection
+    items.iter().enumerate().for_each(|(i, item)| {
+        let y = top + i as i32 * 2;
+        if i == *selection {
+            ctx.print_color_centered(y, YELLOW, BLACK, format!("> {} <", item.label));
+        } else if item.enabled {
+            ctx.print_color_centered(y, WHITE, BLACK, &item.label);
+        } else {
+            ctx.print_color_centered(y, GREY, BLACK, &item.label);
+        }
+    });
+
+    chosen
+}
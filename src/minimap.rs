@@ -0,0 +1,14 @@
+// Whether the minimap of the tiles revealed so far is shown on the HUD
+pub struct Minimap {
+    pub visible: bool,
+}
+
+impl Minimap {
+    pub fn new() -> Self {
+        Self { visible: true }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+}
@@ -0,0 +1,140 @@
+use crate::prelude::*;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The directory morgue files are written to
+const MORGUE_DIR: &str = "morgue";
+
+// The number of the most recent messages included in a morgue file
+const MORGUE_MESSAGES: usize = 50;
+
+// Writes a human-readable account of a finished run, for sharing and picking over afterwards,
+// returning the path it was written to
+pub fn write_morgue(
+    ecs: &World,
+    resources: &Resources,
+    run: &SaveGame,
+    victory: bool,
+) -> std::io::Result<PathBuf> {
+    let morgue = describe_run(ecs, resources, run, victory);
+
+    std::fs::create_dir_all(MORGUE_DIR)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let path = PathBuf::from(MORGUE_DIR).join(format!("{}-{}.txt", run.seed, timestamp));
+    std::fs::write(&path, morgue)?;
+    Ok(path)
+}
+
+fn describe_run(ecs: &World, resources: &Resources, run: &SaveGame, victory: bool) -> String {
+    let stats = resources.get::<RunStats>().unwrap();
+    let mut morgue = String::new();
+
+    // How the run went
+    let _ = writeln!(morgue, "Dungeon Crawler morgue file");
+    let _ = writeln!(morgue);
+    let _ = writeln!(morgue, "Seed:       {}", run.seed);
+    let _ = writeln!(morgue, "Difficulty: {}", run.difficulty.name);
+    let _ = writeln!(morgue, "Class:      {}", run.class.name);
+    let _ = writeln!(morgue, "Fate:       {}", stats.fate(victory));
+    let _ = writeln!(morgue, "Depth:      {}", stats.deepest());
+    let _ = writeln!(morgue, "Turns:      {}", stats.turns);
+    let _ = writeln!(morgue);
+
+    // The player's character
+    let mut players =
+        <(Entity, &Name, &Experience, &Health, &Damage)>::query().filter(component::<Player>());
+    if let Some((player, name, experience, health, damage)) = players.iter(ecs).next() {
+        let bonus = <(&Equipped, &StatBonus)>::query()
+            .iter(ecs)
+            .filter(|(equipped, _)| equipped.owner == *player)
+            .fold((0, 0), |(attack, defense), (_, bonus)| {
+                (attack + bonus.attack, defense + bonus.defense)
+            });
+        let gold = ecs
+            .entry_ref(*player)
+            .ok()
+            .and_then(|entry| entry.get_component::<Gold>().ok().map(|gold| gold.0))
+            .unwrap_or(0);
+        let _ = writeln!(morgue, "== Character ==");
+        let _ = writeln!(morgue, "Name:       {}", name.0);
+        let _ = writeln!(morgue, "Level:      {}", experience.level);
+        let _ = writeln!(
+            morgue,
+            "Experience: {} / {}",
+            experience.xp,
+            experience.next_level_xp()
+        );
+        let _ = writeln!(morgue, "Health:     {} / {}", health.current, health.max);
+        let _ = writeln!(morgue, "Attack:     {}", damage.0 + bonus.0);
+        let _ = writeln!(morgue, "Defense:    {}", bonus.1);
+        let _ = writeln!(morgue, "Gold:       {}", gold);
+        let _ = writeln!(morgue);
+
+        // What they were carrying
+        let _ = writeln!(morgue, "== Inventory ==");
+        let mut items = <(&Carried, &Name, Option<&Equipped>)>::query().filter(component::<Item>());
+        let mut carried = 0;
+        items
+            .iter(ecs)
+            .filter(|(carried, _, _)| carried.0 == *player)
+            .for_each(|(_, name, equipped)| {
+                let suffix = if equipped.is_some() {
+                    " (equipped)"
+                } else {
+                    ""
+                };
+                let _ = writeln!(morgue, "{}{}", name.0, suffix);
+                carried += 1;
+            });
+        if carried == 0 {
+            let _ = writeln!(morgue, "Nothing.");
+        }
+        let _ = writeln!(morgue);
+    }
+
+    // The monsters slain along the way
+    let _ = writeln!(morgue, "== Kills ({}) ==", stats.total_kills());
+    stats.kills.iter().for_each(|(monster, count)| {
+        let _ = writeln!(morgue, "{:<20} {}", monster, count);
+    });
+    let _ = writeln!(morgue);
+
+    // The last few messages before the end
+    let _ = writeln!(morgue, "== Last messages ==");
+    if let Some(log) = resources.get::<GameLog>() {
+        log.recent(MORGUE_MESSAGES).iter().for_each(|message| {
+            let _ = writeln!(morgue, "{}", message);
+        });
+    }
+    let _ = writeln!(morgue);
+
+    // The map as far as the player explored it, with the player marked where they ended up
+    let _ = writeln!(morgue, "== Map ==");
+    let map = resources.get::<Map>().unwrap();
+    let theme = resources.get::<Box<dyn MapTheme>>().unwrap();
+    let player_pos = <&Point>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .next()
+        .copied();
+    for y in 0..SCREEN_HEIGHT {
+        let row: String = (0..SCREEN_WIDTH)
+            .map(|x| {
+                let idx = map_idx(x, y);
+                if player_pos == Some(Point::new(x, y)) {
+                    '@'
+                } else if map.revealed_tiles[idx] {
+                    to_char(theme.tile_to_render(map.tiles[idx]) as u8)
+                } else {
+                    ' '
+                }
+            })
+            .collect();
+        let _ = writeln!(morgue, "{}", row.trim_end());
+    }
+
+    morgue
+}
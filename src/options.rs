@@ -0,0 +1,144 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// The file the options are kept in between runs
+const OPTIONS_FILE: &str = "options.ron";
+
+// The keys the player can move with, in addition to the cursor keys
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovementKeys {
+    Cursor,
+    Numpad,
+}
+
+// The theme maps are drawn with, or random to let each map pick its own
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    #[default]
+    Random,
+    Dungeon,
+    Forest,
+}
+
+// How the camera follows the player, either keeping them in the center of the screen or only
+// scrolling once they near its edge
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CameraMode {
+    #[default]
+    Centered,
+    Deadzone,
+}
+
+// The options the player can change from the options screen
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Options {
+    pub movement_keys: MovementKeys,
+    pub theme: ThemeChoice,
+    #[serde(default)]
+    pub camera: CameraMode,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self {
+            movement_keys: MovementKeys::Cursor,
+            theme: ThemeChoice::Random,
+            camera: CameraMode::Centered,
+        }
+    }
+
+    // Loads the options, falling back to the defaults if they have never been saved
+    pub fn load() -> Self {
+        std::fs::read_to_string(OPTIONS_FILE)
+            .ok()
+            .and_then(|options| ron::from_str(&options).ok())
+            .unwrap_or_else(Self::new)
+    }
+
+    pub fn save(&self) {
+        if let Ok(options) = ron::to_string(self) {
+            if let Err(e) = std::fs::write(OPTIONS_FILE, options) {
+                eprintln!("Unable to save options: {}", e);
+            }
+        }
+    }
+
+    // Translates a key press according to the key bindings, so that the systems only ever need to
+    // handle the cursor keys
+    pub fn translate(&self, key: Option<VirtualKeyCode>) -> Option<VirtualKeyCode> {
+        match (self.movement_keys, key) {
+            (MovementKeys::Numpad, Some(VirtualKeyCode::Numpad4)) => Some(VirtualKeyCode::Left),
+            (MovementKeys::Numpad, Some(VirtualKeyCode::Numpad6)) => Some(VirtualKeyCode::Right),
+            (MovementKeys::Numpad, Some(VirtualKeyCode::Numpad8)) => Some(VirtualKeyCode::Up),
+            (MovementKeys::Numpad, Some(VirtualKeyCode::Numpad2)) => Some(VirtualKeyCode::Down),
+            _ => key,
+        }
+    }
+}
+
+impl MovementKeys {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MovementKeys::Cursor => "Cursor keys",
+            MovementKeys::Numpad => "Cursor keys and numpad",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            MovementKeys::Cursor => MovementKeys::Numpad,
+            MovementKeys::Numpad => MovementKeys::Cursor,
+        }
+    }
+}
+
+impl ThemeChoice {
+    // Gets the theme chosen to override the map's own, if any
+    pub fn theme(&self) -> Option<Box<dyn MapTheme>> {
+        match self {
+            ThemeChoice::Random => None,
+            ThemeChoice::Dungeon => Some(DungeonTheme::new()),
+            ThemeChoice::Forest => Some(ForestTheme::new()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeChoice::Random => "Random",
+            ThemeChoice::Dungeon => "Dungeon",
+            ThemeChoice::Forest => "Forest",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ThemeChoice::Random => ThemeChoice::Dungeon,
+            ThemeChoice::Dungeon => ThemeChoice::Forest,
+            ThemeChoice::Forest => ThemeChoice::Random,
+        }
+    }
+}
+
+impl CameraMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CameraMode::Centered => "Centered",
+            CameraMode::Deadzone => "Scroll near the edges",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            CameraMode::Centered => CameraMode::Deadzone,
+            CameraMode::Deadzone => CameraMode::Centered,
+        }
+    }
+
+    // Gets how close the player may come to the edge of the screen before the camera scrolls
+    pub fn deadzone(&self) -> i32 {
+        match self {
+            CameraMode::Centered => 0,
+            CameraMode::Deadzone => 6,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// The file the player classes are read from
+const CLASS_FILE: &str = "resources/classes.ron";
+
+// The abilities that set a class apart:
+//  * Cleave strikes every other enemy next to the player along with the one they attack
+//  * Trap sense spots hidden traps as soon as they come into view
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ability {
+    Cleave,
+    TrapSense,
+}
+
+// The items a class can start the run carrying. Equipment starts out equipped
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StartingItem {
+    Dagger,
+    Sword,
+    LeatherArmor,
+    PotionOfHaste,
+    PotionOfRegeneration,
+    Ration,
+}
+
+// A template for the player, chosen when starting a run. The health and sight are those of a run at
+// normal difficulty, which the other difficulties adjust. It's recorded in saves so that a run can be
+// replayed even after the classes are changed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerClass {
+    pub name: String,
+    pub description: String,
+    pub health: i32,
+    pub fov: i32,
+    pub damage: i32,
+    pub mana: i32,
+    pub spells: Vec<Spell>,
+    pub items: Vec<StartingItem>,
+    pub ability: Option<Ability>,
+}
+
+impl PlayerClass {
+    // Loads the player classes, falling back to the built in ones if the file is missing or can't be
+    // read
+    pub fn load_classes() -> Vec<Self> {
+        std::fs::read_to_string(CLASS_FILE)
+            .ok()
+            .and_then(|classes| ron::from_str::<Vec<Self>>(&classes).ok())
+            .filter(|classes| !classes.is_empty())
+            .unwrap_or_else(Self::default_classes)
+    }
+
+    fn default_classes() -> Vec<Self> {
+        vec![
+            Self {
+                name: "Warrior".to_string(),
+                description: "Tough and well armed, and cleaves through every foe around them."
+                    .to_string(),
+                health: 12,
+                fov: 7,
+                damage: 1,
+                mana: 0,
+                spells: Vec::new(),
+                items: vec![StartingItem::Sword, StartingItem::LeatherArmor],
+                ability: Some(Ability::Cleave),
+            },
+            Self {
+                name: "Rogue".to_string(),
+                description: "Sharp eyed enough to spot traps the moment they come into view."
+                    .to_string(),
+                health: 10,
+                fov: 10,
+                damage: 1,
+                mana: 6,
+                spells: vec![Spell::Blink],
+                items: vec![
+                    StartingItem::Dagger,
+                    StartingItem::PotionOfHaste,
+                    StartingItem::Ration,
+                ],
+                ability: Some(Ability::TrapSense),
+            },
+            Self {
+                name: "Mage".to_string(),
+                description: "Frail, but starts out able to hurl bolts of fire.".to_string(),
+                health: 7,
+                fov: 8,
+                damage: 1,
+                mana: 15,
+                spells: vec![Spell::Firebolt, Spell::Heal],
+                items: vec![StartingItem::PotionOfRegeneration],
+                ability: None,
+            },
+        ]
+    }
+}
+
+// Runs saved before there were classes played as an adventurer who knew every spell
+impl Default for PlayerClass {
+    fn default() -> Self {
+        Self {
+            name: "Adventurer".to_string(),
+            description: String::new(),
+            health: 10,
+            fov: 8,
+            damage: 1,
+            mana: 10,
+            spells: vec![Spell::Firebolt, Spell::Blink, Spell::Heal],
+            items: Vec::new(),
+            ability: None,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::prelude::*;
+
+// The number of frames to wait between each input at each replay speed, slowest first
+const SPEEDS: [i32; 6] = [30, 15, 8, 4, 2, 1];
+
+// The progress through a recorded run being played back
+pub struct Replay {
+    pub inputs: Vec<RecordedInput>,
+    pub next: usize,
+    pub speed: usize,
+    pub paused: bool,
+    pub frames: i32,
+    pub out_of_sync: bool,
+}
+
+impl Replay {
+    pub fn new(inputs: Vec<RecordedInput>) -> Self {
+        Self {
+            inputs,
+            next: 0,
+            speed: 2,
+            paused: false,
+            frames: 0,
+            out_of_sync: false,
+        }
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = usize::min(self.speed + 1, SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.inputs.len()
+    }
+
+    // Counts off a frame, returning the next input once it's due, or straight away if stepping
+    pub fn advance(&mut self, step: bool) -> Option<RecordedInput> {
+        self.frames += 1;
+        if !(step || !self.paused && self.frames >= SPEEDS[self.speed]) {
+            return None;
+        }
+        self.frames = 0;
+        let input = self.inputs.get(self.next).copied();
+        if input.is_some() {
+            self.next += 1;
+        }
+        input
+    }
+}
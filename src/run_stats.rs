@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+
+// The depth of the final level, where the Amulet of Yala lies
+pub const FINAL_DEPTH: i32 = 3;
+
+// The statistics kept over the course of a run
+pub struct RunStats {
+    pub depth: i32,
+    pub turns: i32,
+    pub kills: BTreeMap<String, i32>,
+    pub killed_by: Option<String>,
+    // Whether the player has taken the amulet and is making their way back to the surface
+    pub returning: bool,
+}
+
+impl RunStats {
+    pub fn new() -> Self {
+        Self {
+            depth: 1,
+            turns: 0,
+            kills: BTreeMap::new(),
+            killed_by: None,
+            returning: false,
+        }
+    }
+
+    // Records what dealt the player their fatal wound, keeping the first should anything else pile on
+    pub fn player_killed_by(&mut self, cause: impl Into<String>) {
+        if self.killed_by.is_none() {
+            self.killed_by = Some(cause.into());
+        }
+    }
+
+    // Describes how the run ended
+    pub fn fate(&self, victory: bool) -> String {
+        if victory && self.returning {
+            "Escaped with the Amulet of Yala".to_string()
+        } else if victory {
+            "Claimed the Amulet of Yala".to_string()
+        } else {
+            format!(
+                "Killed by {}",
+                self.killed_by.as_deref().unwrap_or("something")
+            )
+        }
+    }
+
+    // The deepest level the player has reached, which is the final one once they've turned back
+    pub fn deepest(&self) -> i32 {
+        if self.returning {
+            FINAL_DEPTH
+        } else {
+            self.depth
+        }
+    }
+
+    pub fn total_kills(&self) -> i32 {
+        self.kills.values().sum()
+    }
+}
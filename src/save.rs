@@ -0,0 +1,118 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The file a run in progress is saved to
+const SAVE_FILE: &str = "savegame.ron";
+
+// The directory replays of past runs are kept in
+const REPLAY_DIR: &str = "replays";
+
+// A key the player pressed, along with the turn it was pressed on so that a replay can tell if it
+// has drifted out of step with the original run
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub turn: i32,
+    pub key: VirtualKeyCode,
+}
+
+// A record of a run, from which it can be restored by building the same dungeon from its seed and
+// replaying the keys the player pressed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub goal: Goal,
+    #[serde(default)]
+    pub class: PlayerClass,
+    // The theme chosen in the options when the run started, as it decides the monsters spawned
+    #[serde(default)]
+    pub theme: ThemeChoice,
+    // When the run started, in seconds since the Unix epoch, which names the file it's recorded to
+    #[serde(default)]
+    pub started: u64,
+    pub inputs: Vec<RecordedInput>,
+}
+
+impl SaveGame {
+    pub fn new(
+        seed: u64,
+        difficulty: Difficulty,
+        goal: Goal,
+        class: PlayerClass,
+        theme: ThemeChoice,
+    ) -> Self {
+        Self {
+            seed,
+            difficulty,
+            goal,
+            class,
+            theme,
+            started: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs()),
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn exists() -> bool {
+        std::path::Path::new(SAVE_FILE).exists()
+    }
+
+    pub fn load() -> Option<Self> {
+        Self::load_from(Path::new(SAVE_FILE))
+    }
+
+    pub fn load_from(path: &Path) -> Option<Self> {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|save| ron::from_str(&save).ok())
+    }
+
+    pub fn save(&self) {
+        if let Ok(save) = ron::to_string(self) {
+            if let Err(e) = std::fs::write(SAVE_FILE, save) {
+                eprintln!("Unable to save the game: {}", e);
+            }
+        }
+    }
+
+    // Deletes the saved game, as a run can't be continued once it's over
+    pub fn delete() {
+        let _ = std::fs::remove_file(SAVE_FILE);
+    }
+
+    // Writes the run so far to the replay directory, replacing any earlier recording of it, so that
+    // it can be watched back later
+    pub fn save_replay(&self) {
+        let path = PathBuf::from(REPLAY_DIR).join(format!("{}-{}.ron", self.seed, self.started));
+        if let Ok(replay) = ron::to_string(self) {
+            let result =
+                std::fs::create_dir_all(REPLAY_DIR).and_then(|_| std::fs::write(path, replay));
+            if let Err(e) = result {
+                eprintln!("Unable to save the replay: {}", e);
+            }
+        }
+    }
+
+    // Lists the saved replays, most recent first
+    pub fn replays() -> Vec<PathBuf> {
+        let mut replays: Vec<PathBuf> = std::fs::read_dir(REPLAY_DIR)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        replays.sort_by_key(|path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        });
+        replays.reverse();
+        replays
+    }
+}
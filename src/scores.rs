@@ -0,0 +1,152 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// The file the high scores are kept in between runs
+const SCORES_FILE: &str = "scores.ron";
+
+// The number of runs kept in the high score table
+const MAX_SCORES: usize = 10;
+
+// A record of a finished run, and how it ended
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub depth: i32,
+    pub turns: i32,
+    pub kills: i32,
+    pub fate: String,
+    pub score: i32,
+}
+
+impl Score {
+    // Scores a finished run, rewarding the player for going deep, killing monsters, hoarding gold
+    // and, above all, claiming the amulet
+    pub fn new(
+        seed: u64,
+        difficulty: Difficulty,
+        stats: &RunStats,
+        gold: i32,
+        victory: bool,
+    ) -> Self {
+        // Carrying the amulet all the way out is worth as much again as claiming it
+        let bonus = match (victory, stats.returning) {
+            (true, true) => 2000,
+            (true, false) => 1000,
+            _ => 0,
+        };
+        let score = stats.deepest() * 100 + stats.total_kills() * 10 + gold + bonus;
+        Self {
+            seed,
+            difficulty,
+            depth: stats.deepest(),
+            turns: stats.turns,
+            kills: stats.total_kills(),
+            fate: stats.fate(victory),
+            score,
+        }
+    }
+}
+
+// The best runs so far, highest score first
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScores {
+    pub scores: Vec<Score>,
+}
+
+impl HighScores {
+    // Loads the high scores, starting an empty table if there are none yet
+    pub fn load() -> Self {
+        std::fs::read_to_string(SCORES_FILE)
+            .ok()
+            .and_then(|scores| ron::from_str(&scores).ok())
+            .unwrap_or(Self { scores: Vec::new() })
+    }
+
+    pub fn save(&self) {
+        if let Ok(scores) = ron::to_string(self) {
+            if let Err(e) = std::fs::write(SCORES_FILE, scores) {
+                eprintln!("Unable to save the high scores: {}", e);
+            }
+        }
+    }
+
+    // Adds a score to the table, returning its rank if it was good enough to be kept
+    pub fn add(&mut self, score: Score) -> Option<usize> {
+        let rank = self
+            .scores
+            .iter()
+            .position(|s| s.score < score.score)
+            .unwrap_or(self.scores.len());
+        self.scores.insert(rank, score);
+        self.scores.truncate(MAX_SCORES);
+        if rank < MAX_SCORES {
+            Some(rank)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(seed: u64, score: i32) -> Score {
+        Score {
+            seed,
+            difficulty: Difficulty {
+                name: "Normal".to_string(),
+                num_monsters: 50,
+                monster_health_bonus: 0,
+                player_health: 10,
+                player_fov: 8,
+                wait_heal: 1,
+            },
+            depth: 1,
+            turns: 0,
+            kills: 0,
+            fate: String::new(),
+            score,
+        }
+    }
+
+    fn seeds(table: &HighScores) -> Vec<u64> {
+        table.scores.iter().map(|score| score.seed).collect()
+    }
+
+    #[test]
+    fn add_keeps_the_table_sorted_highest_first() {
+        let mut table = HighScores { scores: Vec::new() };
+        assert_eq!(table.add(score(1, 100)), Some(0));
+        assert_eq!(table.add(score(2, 300)), Some(0));
+        assert_eq!(table.add(score(3, 200)), Some(1));
+        assert_eq!(seeds(&table), vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn add_ranks_a_tie_below_the_earlier_run() {
+        let mut table = HighScores { scores: Vec::new() };
+        table.add(score(1, 100));
+        assert_eq!(table.add(score(2, 100)), Some(1));
+        assert_eq!(seeds(&table), vec![1, 2]);
+    }
+
+    #[test]
+    fn add_truncates_the_table_to_the_best_runs() {
+        let mut table = HighScores { scores: Vec::new() };
+        for seed in 0..MAX_SCORES as u64 {
+            table.add(score(seed, 100 + seed as i32));
+        }
+
+        // A better run pushes the worst off the bottom of the table
+        assert_eq!(table.add(score(99, 1000)), Some(0));
+        assert_eq!(table.scores.len(), MAX_SCORES);
+        assert!(!seeds(&table).contains(&0));
+
+        // A run worse than everything in a full table isn't kept
+        assert_eq!(table.add(score(100, 1)), None);
+        assert_eq!(table.scores.len(), MAX_SCORES);
+        assert!(!seeds(&table).contains(&100));
+    }
+}
@@ -1,5 +1,18 @@
 use crate::prelude::*;
 
+// The kinds of monster that roam the dungeon
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MonsterKind {
+    Goblin,
+    Orc,
+    Ettin,
+    Wolf,
+    Skeleton,
+}
+
+// A table of weighted monster spawns, which varies with the theme of a level and its depth
+pub type SpawnTable = WeightedTable<MonsterKind>;
+
 pub fn spawn_amulet_of_yala(ecs: &mut World, pos: Point) {
     ecs.push((
         Item,
@@ -13,8 +26,9 @@ pub fn spawn_amulet_of_yala(ecs: &mut World, pos: Point) {
     ));
 }
 
-pub fn spawn_player(ecs: &mut World, pos: Point) {
-    ecs.push((
+pub fn spawn_player(ecs: &mut World, pos: Point, difficulty: &Difficulty, class: &PlayerClass) {
+    let health = difficulty.class_health(class);
+    let player = ecs.push((
         Player,
         pos,
         Render {
@@ -22,24 +36,345 @@ pub fn spawn_player(ecs: &mut World, pos: Point) {
             glyph: to_cp437('@'),
         },
         Health {
-            current: 10,
-            max: 10,
+            current: health,
+            max: health,
         },
-        FieldOfView::new(8),
+        FieldOfView::new(difficulty.class_fov(class)),
     ));
+    if let Some(mut entry) = ecs.entry(player) {
+        entry.add_component(Name("Hero".to_string()));
+        entry.add_component(Experience { level: 1, xp: 0 });
+        entry.add_component(Damage(class.damage));
+        entry.add_component(RestHealing(difficulty.wait_heal));
+        entry.add_component(Gold(0));
+        entry.add_component(Hunger { turns: 1200 });
+        entry.add_component(Mana {
+            current: class.mana,
+            max: class.mana,
+        });
+        entry.add_component(Spellbook(class.spells.clone()));
+        match class.ability {
+            Some(Ability::Cleave) => entry.add_component(Cleave),
+            Some(Ability::TrapSense) => entry.add_component(TrapSense),
+            None => {}
+        }
+    }
+
+    // Hand the player the class's starting kit, wearing any equipment
+    class.items.iter().for_each(|item| {
+        let item = match item {
+            StartingItem::Dagger => ecs.push(equipment_item(dagger())),
+            StartingItem::Sword => ecs.push(equipment_item(sword())),
+            StartingItem::LeatherArmor => ecs.push(equipment_item(leather_armor())),
+            StartingItem::PotionOfHaste => ecs.push(potion_item(haste_potion())),
+            StartingItem::PotionOfRegeneration => ecs.push(potion_item(regeneration_potion())),
+            StartingItem::Ration => ecs.push(ration()),
+        };
+        if let Some(mut entry) = ecs.entry(item) {
+            entry.add_component(Carried(player));
+            if let Ok(equippable) = entry.get_component::<Equippable>().copied() {
+                entry.add_component(Equipped {
+                    owner: player,
+                    slot: equippable.slot,
+                });
+            }
+        }
+    });
+}
+
+pub fn spawn_entity(
+    ecs: &mut World,
+    rng: &mut RandomNumberGenerator,
+    pos: Point,
+    monsters: &SpawnTable,
+) {
+    match rng.roll_dice(1, 10) {
+        1 => spawn_potion(ecs, rng, pos),
+        2 => spawn_equipment(ecs, rng, pos),
+        3 => spawn_ration(ecs, pos),
+        _ => spawn_monster(ecs, rng, pos, monsters),
+    }
 }
 
-pub fn spawn_monster(ecs: &mut World, rng: &mut RandomNumberGenerator, pos: Point) {
-    let (hp, name, glyph) = match rng.roll_dice(1, 10) {
-        1..=8 => goblin(),
-        _ => orc(),
+pub fn spawn_monster(
+    ecs: &mut World,
+    rng: &mut RandomNumberGenerator,
+    pos: Point,
+    monsters: &SpawnTable,
+) {
+    match monsters.roll(rng).unwrap_or(MonsterKind::Goblin) {
+        MonsterKind::Goblin => {
+            spawn_monster_entity(ecs, pos, goblin(), goblin_loot(), WHITE);
+        }
+        MonsterKind::Orc => {
+            spawn_orc(ecs, pos);
+        }
+        MonsterKind::Ettin => {
+            spawn_ettin(ecs, pos);
+        }
+        MonsterKind::Wolf => {
+            spawn_wolf(ecs, pos);
+        }
+        MonsterKind::Skeleton => {
+            spawn_monster_entity(ecs, pos, skeleton(), skeleton_loot(), SLATE_GRAY);
+        }
+    }
+}
+
+pub fn spawn_war_band(ecs: &mut World, pack: Pack, positions: &[Point]) {
+    positions.iter().for_each(|pos| {
+        let orc = spawn_orc(ecs, *pos);
+        if let Some(mut entry) = ecs.entry(orc) {
+            entry.add_component(pack);
+        }
+    });
+}
+
+pub fn spawn_potion(ecs: &mut World, rng: &mut RandomNumberGenerator, pos: Point) {
+    let potion = ecs.push(potion(rng));
+    if let Some(mut entry) = ecs.entry(potion) {
+        entry.add_component(pos);
+    }
+}
+
+pub fn spawn_equipment(ecs: &mut World, rng: &mut RandomNumberGenerator, pos: Point) {
+    let equipment = ecs.push(equipment(rng));
+    if let Some(mut entry) = ecs.entry(equipment) {
+        entry.add_component(pos);
+    }
+}
+
+pub fn spawn_ration(ecs: &mut World, pos: Point) {
+    let ration = ecs.push(ration());
+    if let Some(mut entry) = ecs.entry(ration) {
+        entry.add_component(pos);
+    }
+}
+
+pub fn spawn_shopkeeper(ecs: &mut World, rng: &mut RandomNumberGenerator, pos: Point) {
+    let shopkeeper = ecs.push((
+        Shopkeeper,
+        pos,
+        Render {
+            color: ColorPair::new(GOLD, BLACK),
+            glyph: to_cp437('@'),
+        },
+        Name("Shopkeeper".to_string()),
+        Gold(100),
+    ));
+
+    // Stock the shop with a few items for sale
+    for _ in 0..rng.range(3, 6) {
+        let item = match rng.range(0, 3) {
+            0 => ecs.push(potion(rng)),
+            1 => ecs.push(equipment(rng)),
+            _ => ecs.push(ration()),
+        };
+        if let Some(mut entry) = ecs.entry(item) {
+            entry.add_component(Carried(shopkeeper));
+        }
+    }
+}
+
+pub fn spawn_trap(ecs: &mut World, rng: &mut RandomNumberGenerator, pos: Point) {
+    // Poisoned spikes keep hurting whoever survives the fall
+    let poison = AppliesEffect {
+        kind: StatusEffectKind::Poison,
+        duration: 3,
+        magnitude: 1,
+    };
+    let (name, color, kind, effect) = match rng.roll_dice(1, 6) {
+        1..=2 => ("Spike Pit", RED, TrapKind::SpikePit, None),
+        3 => (
+            "Poisoned Spike Pit",
+            GREEN,
+            TrapKind::SpikePit,
+            Some(poison),
+        ),
+        4..=5 => ("Alarm Plate", YELLOW, TrapKind::Alarm, None),
+        _ => ("Teleport Plate", MAGENTA, TrapKind::Teleport, None),
     };
+    let trap = ecs.push((
+        Trap { kind },
+        Hidden,
+        pos,
+        Render {
+            color: ColorPair::new(color, BLACK),
+            glyph: to_cp437('^'),
+        },
+        Name(name.to_string()),
+        Damage(2),
+    ));
+    if let (Some(effect), Some(mut entry)) = (effect, ecs.entry(trap)) {
+        entry.add_component(effect);
+    }
+}
 
-    ecs.push((
+pub fn spawn_loot(
+    commands: &mut CommandBuffer,
+    rng: &mut RandomNumberGenerator,
+    pos: Point,
+    loot: Loot,
+) {
+    let drop = match loot {
+        Loot::Gold(max) => commands.push((
+            Render {
+                color: ColorPair::new(GOLD, BLACK),
+                glyph: to_cp437('$'),
+            },
+            Name("Gold".to_string()),
+            Gold(rng.range(1, max + 1)),
+        )),
+        Loot::Potion => commands.push(potion(rng)),
+        Loot::Equipment => commands.push(equipment(rng)),
+    };
+    commands.add_component(drop, pos);
+}
+
+pub fn potion(rng: &mut RandomNumberGenerator) -> (Item, Render, Name, AppliesEffect, Price) {
+    potion_item(match rng.roll_dice(1, 3) {
+        1 => haste_potion(),
+        _ => regeneration_potion(),
+    })
+}
+
+pub fn potion_item(
+    (name, color, effect, price): (String, (u8, u8, u8), AppliesEffect, i32),
+) -> (Item, Render, Name, AppliesEffect, Price) {
+    (
+        Item,
+        Render {
+            color: ColorPair::new(color, BLACK),
+            glyph: to_cp437('!'),
+        },
+        Name(name),
+        effect,
+        Price(price),
+    )
+}
+
+pub fn equipment(
+    rng: &mut RandomNumberGenerator,
+) -> (Item, Render, Name, Equippable, StatBonus, Price) {
+    equipment_item(match rng.roll_dice(1, 6) {
+        1..=3 => dagger(),
+        4 => sword(),
+        _ => leather_armor(),
+    })
+}
+
+pub fn equipment_item(
+    (name, glyph, slot, bonus, price): (String, FontCharType, EquipmentSlot, StatBonus, i32),
+) -> (Item, Render, Name, Equippable, StatBonus, Price) {
+    (
+        Item,
+        Render {
+            color: ColorPair::new(WHITE, BLACK),
+            glyph,
+        },
+        Name(name),
+        Equippable { slot },
+        bonus,
+        Price(price),
+    )
+}
+
+pub fn ration() -> (Item, Render, Name, Nourishes, Price) {
+    (
+        Item,
+        Render {
+            color: ColorPair::new(SANDY_BROWN, BLACK),
+            glyph: to_cp437('%'),
+        },
+        Name("Ration".to_string()),
+        Nourishes(800),
+        Price(5),
+    )
+}
+
+// Ettins hurl rocks at the player from a distance, leaving them dazed
+fn spawn_ettin(ecs: &mut World, pos: Point) -> Entity {
+    let ettin = spawn_monster_entity(ecs, pos, ettin(), ettin_loot(), WHITE);
+    if let Some(mut entry) = ecs.entry(ettin) {
+        entry.add_component(RangedAttack {
+            range: 5,
+            damage: 1,
+        });
+        entry.add_component(AppliesEffect {
+            kind: StatusEffectKind::Confusion,
+            duration: 3,
+            magnitude: 5,
+        });
+    }
+    ettin
+}
+
+// Orcs fight with poisoned blades, and can survive a hit, so they run off to recover once badly wounded
+fn spawn_orc(ecs: &mut World, pos: Point) -> Entity {
+    let (hp, name, glyph) = orc();
+    let orc = spawn_monster_entity(ecs, pos, (hp, name, glyph), orc_loot(), WHITE);
+    if let Some(mut entry) = ecs.entry(orc) {
+        entry.add_component(Flees { threshold: hp });
+        entry.add_component(AppliesEffect {
+            kind: StatusEffectKind::Poison,
+            duration: 2,
+            magnitude: 1,
+        });
+    }
+    orc
+}
+
+// The Ogre Chieftain guards the amulet, waiting beside it until disturbed. It shrugs off blows that
+// would fell anything else in the dungeon, and slams the ground to daze anyone who comes close
+pub fn spawn_boss(ecs: &mut World, pos: Point) -> Entity {
+    let boss = ecs.push((
         Enemy,
+        Dormant,
         pos,
         Render {
-            color: ColorPair::new(WHITE, BLACK),
+            color: ColorPair::new(ORANGE_RED, BLACK),
+            glyph: to_cp437('O'),
+        },
+        Health {
+            current: 12,
+            max: 12,
+        },
+        Name("Ogre Chieftain".to_string()),
+        FieldOfView::new(6),
+        Damage(2),
+    ));
+    if let Some(mut entry) = ecs.entry(boss) {
+        entry.add_component(GroundSlam {
+            radius: 2,
+            cooldown: 3,
+            ready_in: 0,
+        });
+        entry.add_component(LootTable::new(vec![(1, Some(Loot::Gold(50)))]));
+    }
+    boss
+}
+
+// Wolves have keen senses, spotting the player from further away than other monsters
+fn spawn_wolf(ecs: &mut World, pos: Point) -> Entity {
+    let wolf = spawn_monster_entity(ecs, pos, wolf(), wolf_loot(), SADDLE_BROWN);
+    if let Some(mut entry) = ecs.entry(wolf) {
+        entry.add_component(FieldOfView::new(9));
+    }
+    wolf
+}
+
+fn spawn_monster_entity(
+    ecs: &mut World,
+    pos: Point,
+    (hp, name, glyph): (i32, String, FontCharType),
+    loot: LootTable,
+    color: (u8, u8, u8),
+) -> Entity {
+    let monster = ecs.push((
+        Enemy,
+        pos,
+        Render {
+            color: ColorPair::new(color, BLACK),
             glyph,
         },
         ChasingPlayer {},
@@ -49,13 +384,134 @@ pub fn spawn_monster(ecs: &mut World, rng: &mut RandomNumberGenerator, pos: Poin
         },
         Name(name),
         FieldOfView::new(6),
+        Damage(1),
     ));
+    if let Some(mut entry) = ecs.entry(monster) {
+        entry.add_component(loot);
+    }
+    monster
 }
 
 pub fn goblin() -> (i32, String, FontCharType) {
     (1, "Goblin".to_string(), to_cp437('g'))
 }
 
+pub fn ettin() -> (i32, String, FontCharType) {
+    (2, "Ettin".to_string(), to_cp437('E'))
+}
+
 pub fn orc() -> (i32, String, FontCharType) {
     (2, "Orc".to_string(), to_cp437('o'))
 }
+
+// The font has no sprites of its own for wolves and skeletons, so they're drawn as tinted goblins and
+// orcs
+pub fn wolf() -> (i32, String, FontCharType) {
+    (1, "Wolf".to_string(), to_cp437('g'))
+}
+
+pub fn skeleton() -> (i32, String, FontCharType) {
+    (3, "Skeleton".to_string(), to_cp437('o'))
+}
+
+pub fn goblin_loot() -> LootTable {
+    LootTable::new(vec![
+        (6, None),
+        (3, Some(Loot::Gold(5))),
+        (1, Some(Loot::Potion)),
+    ])
+}
+
+pub fn ettin_loot() -> LootTable {
+    LootTable::new(vec![
+        (4, None),
+        (5, Some(Loot::Gold(15))),
+        (1, Some(Loot::Potion)),
+    ])
+}
+
+pub fn orc_loot() -> LootTable {
+    LootTable::new(vec![
+        (4, None),
+        (4, Some(Loot::Gold(10))),
+        (1, Some(Loot::Potion)),
+        (1, Some(Loot::Equipment)),
+    ])
+}
+
+pub fn wolf_loot() -> LootTable {
+    LootTable::new(vec![(1, None)])
+}
+
+pub fn skeleton_loot() -> LootTable {
+    LootTable::new(vec![
+        (5, None),
+        (4, Some(Loot::Gold(8))),
+        (1, Some(Loot::Equipment)),
+    ])
+}
+
+pub fn haste_potion() -> (String, (u8, u8, u8), AppliesEffect, i32) {
+    (
+        "Potion of Haste".to_string(),
+        YELLOW,
+        AppliesEffect {
+            kind: StatusEffectKind::Haste,
+            duration: 10,
+            magnitude: 1,
+        },
+        15,
+    )
+}
+
+pub fn regeneration_potion() -> (String, (u8, u8, u8), AppliesEffect, i32) {
+    (
+        "Potion of Regeneration".to_string(),
+        WHITE,
+        AppliesEffect {
+            kind: StatusEffectKind::Regeneration,
+            duration: 5,
+            magnitude: 1,
+        },
+        10,
+    )
+}
+
+pub fn dagger() -> (String, FontCharType, EquipmentSlot, StatBonus, i32) {
+    (
+        "Dagger".to_string(),
+        to_cp437('s'),
+        EquipmentSlot::Weapon,
+        StatBonus {
+            attack: 1,
+            defense: 0,
+        },
+        10,
+    )
+}
+
+pub fn sword() -> (String, FontCharType, EquipmentSlot, StatBonus, i32) {
+    (
+        "Sword".to_string(),
+        to_cp437('S'),
+        EquipmentSlot::Weapon,
+        StatBonus {
+            attack: 2,
+            defense: 0,
+        },
+        25,
+    )
+}
+
+pub fn leather_armor() -> (String, FontCharType, EquipmentSlot, StatBonus, i32) {
+    (
+        "Leather Armor".to_string(),
+        to_cp437('['),
+        EquipmentSlot::Armor,
+        StatBonus {
+            attack: 0,
+            defense: 1,
+        },
+        20,
+    )
+}
@@ -0,0 +1,87 @@
+use super::chasing::SEARCH_TURNS;
+use crate::prelude::*;
+
+// The distance, walked through the dungeon, over which a monster that spots the player alerts its
+// allies
+const ALERT_RADIUS: i32 = 10;
+
+// A system that handles alerts, waking the monsters they reach to hunt for the player
+#[system]
+#[read_component(Alert)]
+#[read_component(Point)]
+#[read_component(Enemy)]
+#[read_component(Pack)]
+#[read_component(MovingRandomly)]
+#[read_component(Dormant)]
+pub fn alert(#[resource] map: &Map, ecs: &SubWorld, commands: &mut CommandBuffer) {
+    let mut alerts = <(Entity, &Alert)>::query();
+    let mut enemies = <(Entity, &Point, Option<&Pack>)>::query().filter(component::<Enemy>());
+
+    alerts.iter(ecs).for_each(|(message, alert)| {
+        // Flood the alert out over the map in the same way as a sound, so that it doesn't carry
+        // through walls
+        let source_idx = map_idx(alert.source.x, alert.source.y);
+        let alert_map = DijkstraMap::new(
+            SCREEN_WIDTH,
+            SCREEN_HEIGHT,
+            &[source_idx],
+            &Acoustics(map),
+            alert.radius as f32,
+        );
+
+        enemies
+            .iter(ecs)
+            .filter(|(_, pos, pack)| {
+                alert_map.map[map_idx(pos.x, pos.y)] < f32::MAX
+                    || (alert.pack.is_some() && pack.copied() == alert.pack)
+            })
+            .for_each(|(entity, _, _)| wake(*entity, alert.target, ecs, commands));
+        commands.remove(*message);
+    });
+}
+
+// Wakes a monster to investigate a point, switching random movers and dormant monsters into chasers
+pub fn wake(entity: Entity, target: Point, ecs: &SubWorld, commands: &mut CommandBuffer) {
+    let entry = ecs.entry_ref(entity).unwrap();
+    if entry.get_component::<MovingRandomly>().is_ok() {
+        commands.remove_component::<MovingRandomly>(entity);
+        commands.add_component(entity, ChasingPlayer);
+    }
+    if entry.get_component::<Dormant>().is_ok() {
+        commands.remove_component::<Dormant>(entity);
+        commands.add_component(entity, ChasingPlayer);
+    }
+
+    commands.add_component(
+        entity,
+        Investigating {
+            target,
+            search_turns: SEARCH_TURNS,
+        },
+    );
+}
+
+// Raises an alert that the player has been spotted by a monster
+pub fn raise_alert(
+    spotter: Entity,
+    spotter_pos: Point,
+    player_pos: Point,
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+) {
+    let pack = ecs
+        .entry_ref(spotter)
+        .unwrap()
+        .get_component::<Pack>()
+        .ok()
+        .copied();
+    commands.push((
+        (),
+        Alert {
+            source: spotter_pos,
+            target: player_pos,
+            radius: ALERT_RADIUS,
+            pack,
+        },
+    ));
+}
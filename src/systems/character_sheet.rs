@@ -0,0 +1,94 @@
+use super::combat::equipment_bonus;
+use crate::prelude::*;
+
+// A system that handles the character sheet, showing the player's progress and the run so far
+#[system]
+#[read_component(Player)]
+#[read_component(Name)]
+#[read_component(Experience)]
+#[read_component(Health)]
+#[read_component(Damage)]
+#[read_component(Equipped)]
+#[read_component(StatBonus)]
+#[read_component(StatusEffect)]
+pub fn character_sheet(
+    ecs: &SubWorld,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] stats: &RunStats,
+) {
+    // Get the player
+    let mut players =
+        <(Entity, &Name, &Experience, &Health, &Damage)>::query().filter(component::<Player>());
+    let (player, name, experience, health, damage) = players.iter(ecs).next().unwrap();
+    let bonus = equipment_bonus(ecs, *player);
+
+    // Gather the lines of the sheet, as pairs of labels and values
+    let mut lines = vec![
+        ("Name".to_string(), name.0.clone()),
+        ("Level".to_string(), experience.level.to_string()),
+        (
+            "Experience".to_string(),
+            format!("{} / {}", experience.xp, experience.next_level_xp()),
+        ),
+        (
+            "Health".to_string(),
+            format!("{} / {}", health.current, health.max),
+        ),
+        ("Attack".to_string(), (damage.0 + bonus.attack).to_string()),
+        ("Defense".to_string(), bonus.defense.to_string()),
+        ("Depth".to_string(), stats.depth.to_string()),
+        ("Turns".to_string(), stats.turns.to_string()),
+        (String::new(), String::new()),
+    ];
+    <(&Equipped, &Name)>::query()
+        .iter(ecs)
+        .filter(|(equipped, _)| equipped.owner == *player)
+        .for_each(|(equipped, name)| {
+            let slot = match equipped.slot {
+                EquipmentSlot::Weapon => "Wielding",
+                EquipmentSlot::Armor => "Wearing",
+            };
+            lines.push((slot.to_string(), name.0.clone()));
+        });
+    <&StatusEffect>::query()
+        .iter(ecs)
+        .filter(|effect| effect.target == *player)
+        .for_each(|effect| {
+            lines.push((
+                effect.kind.name().to_string(),
+                format!("{} turns", effect.remaining_turns),
+            ));
+        });
+    lines.push((String::new(), String::new()));
+    lines.push(("Kills".to_string(), stats.total_kills().to_string()));
+    stats.kills.iter().for_each(|(monster, count)| {
+        lines.push((format!("  {}", monster), count.to_string()));
+    });
+
+    // Draw the sheet
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(2);
+    let top = 15;
+    draw_batch.draw_box(
+        Rect::with_size(50, top, 60, lines.len() as i32 + 5),
+        ColorPair::new(WHITE, BLACK),
+    );
+    draw_batch.print_color_centered(top, " Character ", ColorPair::new(YELLOW, BLACK));
+    lines.iter().enumerate().for_each(|(i, (label, value))| {
+        let y = top + 2 + i as i32;
+        draw_batch.print_color(Point::new(53, y), label, ColorPair::new(YELLOW, BLACK));
+        draw_batch.print(Point::new(75, y), value);
+    });
+    draw_batch.print_color_centered(
+        top + lines.len() as i32 + 4,
+        " Escape to close. ",
+        ColorPair::new(YELLOW, BLACK),
+    );
+    draw_batch.submit(11000).expect("Batch error");
+
+    // Handle the input
+    if let Some(VirtualKeyCode::Escape) | Some(VirtualKeyCode::C) = *key {
+        *turn_state = TurnState::AwaitingInput;
+    }
+}
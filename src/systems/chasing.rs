@@ -1,12 +1,28 @@
+use super::random_move::random_direction;
+use super::status_effects::{afflict, find_status_effect};
 use crate::prelude::*;
 
+// The number of turns a chaser searches around the last place it saw the player before giving up
+pub const SEARCH_TURNS: i32 = 5;
+
 #[system]
 #[read_component(Point)]
 #[read_component(ChasingPlayer)]
 #[read_component(FieldOfView)]
 #[read_component(Health)]
 #[read_component(Player)]
-pub fn chasing(#[resource] map: &Map, ecs: &SubWorld, commands: &mut CommandBuffer) {
+#[read_component(RangedAttack)]
+#[read_component(Investigating)]
+#[read_component(StatusEffect)]
+#[read_component(GroundSlam)]
+#[read_component(Name)]
+pub fn chasing(
+    #[resource] map: &Map,
+    #[resource] rng: &mut RandomNumberGenerator,
+    #[resource] log: &mut GameLog,
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+) {
     // Find the player position
     let mut players = <(&Point, &Player)>::query();
     let player_pos = players.iter(ecs).next().unwrap().0;
@@ -20,11 +36,58 @@ pub fn chasing(#[resource] map: &Map, ecs: &SubWorld, commands: &mut CommandBuff
     let mut chasers = <(Entity, &Point, &ChasingPlayer, &FieldOfView)>::query();
     let mut entities = <(Entity, &Point, &Health)>::query();
     chasers.iter(ecs).for_each(|(chaser, chaser_pos, _, fov)| {
-        // Check if the chaser can see the player. If not, skip
+        // Check if the chaser can see the player. If not, go looking where it was last seen
         if !fov.visible_tiles.contains(player_pos) {
+            investigate(*chaser, *chaser_pos, map, rng, ecs, commands);
             return;
         }
 
+        // Remember where the player was seen, in case the chaser loses sight of them
+        commands.add_component(
+            *chaser,
+            Investigating {
+                target: *player_pos,
+                search_turns: SEARCH_TURNS,
+            },
+        );
+
+        // A chaser that can slam the ground does so once the player is close enough, and it's ready
+        if ground_slam(*chaser, *chaser_pos, *player_pos, ecs, commands, log) {
+            return;
+        }
+
+        // If the chaser attacks from range, fire on the player when within range and the line of
+        // fire is clear. Ranged attackers otherwise move to get a clear shot, but keep their distance
+        // rather than closing to melee
+        let ranged = ecs
+            .entry_ref(*chaser)
+            .unwrap()
+            .get_component::<RangedAttack>()
+            .ok()
+            .copied();
+        if let Some(ranged) = ranged {
+            let distance = DistanceAlg::Pythagoras.distance2d(*chaser_pos, *player_pos);
+            if distance <= ranged.range as f32 {
+                if let Some(path) = line_of_fire(*chaser_pos, *player_pos, map, ecs) {
+                    let player = <Entity>::query()
+                        .filter(component::<Player>())
+                        .iter(ecs)
+                        .copied()
+                        .next()
+                        .unwrap();
+                    commands.push((
+                        (),
+                        WantsToAttack {
+                            attacker: *chaser,
+                            victim: player,
+                        },
+                    ));
+                    commands.push(((), Projectile { path }));
+                    return;
+                }
+            }
+        }
+
         // Get the next destination for the chaser, as the exit from the current tile with the lowest
         // cost to move to the player's position. If there is one, move the chaser
         let chaser_idx = map_idx(chaser_pos.x, chaser_pos.y);
@@ -38,6 +101,21 @@ pub fn chasing(#[resource] map: &Map, ecs: &SubWorld, commands: &mut CommandBuff
                 *player_pos
             };
 
+            // A ranged attacker holds its position rather than stepping up next to the player
+            if ranged.is_some()
+                && DistanceAlg::Pythagoras.distance2d(destination, *player_pos) < 1.5
+            {
+                return;
+            }
+
+            // A confused chaser may stumble off in a random direction instead
+            let destination = match find_status_effect(ecs, *chaser, StatusEffectKind::Confusion) {
+                Some(confusion) if rng.range(0, 10) < confusion.magnitude => {
+                    *chaser_pos + random_direction(rng)
+                }
+                _ => destination,
+            };
+
             // See if the destination matches some other entity position, and resolve
             let mut did_attack = false;
             entities
@@ -75,3 +153,142 @@ pub fn chasing(#[resource] map: &Map, ecs: &SubWorld, commands: &mut CommandBuff
         }
     })
 }
+
+// Moves a chaser that cannot see the player towards the point it is investigating, then searches
+// the area around it for a few turns before giving up
+fn investigate(
+    chaser: Entity,
+    chaser_pos: Point,
+    map: &Map,
+    rng: &mut RandomNumberGenerator,
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+) {
+    let investigating = match ecs
+        .entry_ref(chaser)
+        .unwrap()
+        .get_component::<Investigating>()
+    {
+        Ok(investigating) => *investigating,
+        Err(_) => return,
+    };
+
+    let destination = if chaser_pos != investigating.target {
+        // Path towards the point of interest. If it can't be reached, give up
+        let path = a_star_search(
+            map_idx(chaser_pos.x, chaser_pos.y),
+            map_idx(investigating.target.x, investigating.target.y),
+            map,
+        );
+        if !path.success || path.steps.len() < 2 {
+            commands.remove_component::<Investigating>(chaser);
+            return;
+        }
+        map.index_to_point2d(path.steps[1])
+    } else if investigating.search_turns > 0 {
+        // Search around the point of interest, wandering off and returning to it
+        commands.add_component(
+            chaser,
+            Investigating {
+                search_turns: investigating.search_turns - 1,
+                ..investigating
+            },
+        );
+        chaser_pos + random_direction(rng)
+    } else {
+        // The search turned up nothing, so give up
+        commands.remove_component::<Investigating>(chaser);
+        return;
+    };
+
+    // Only move if nobody else is standing in the way
+    let mut entities = <&Point>::query().filter(component::<Health>());
+    if !entities.iter(ecs).any(|pos| *pos == destination) {
+        commands.push((
+            (),
+            WantsToMove {
+                entity: chaser,
+                destination,
+            },
+        ));
+    }
+}
+
+// Finds the path of a projectile between two points, if no walls or other entities block it
+fn line_of_fire(from: Point, to: Point, map: &Map, ecs: &SubWorld) -> Option<Vec<Point>> {
+    let path: Vec<Point> = line2d(LineAlg::Bresenham, from, to)
+        .into_iter()
+        .filter(|pos| *pos != from && *pos != to)
+        .collect();
+
+    let mut blockers = <&Point>::query().filter(component::<Health>());
+    let is_clear = path
+        .iter()
+        .all(|pos| map.can_enter_tile(*pos) && !blockers.iter(ecs).any(|blocker| blocker == pos));
+
+    if is_clear {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+// Brings a chaser's ground slam down on the player if it's ready and the player is in reach,
+// returning whether it did
+fn ground_slam(
+    chaser: Entity,
+    chaser_pos: Point,
+    player_pos: Point,
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    log: &mut GameLog,
+) -> bool {
+    let entry = ecs.entry_ref(chaser).unwrap();
+    let slam = match entry.get_component::<GroundSlam>() {
+        Ok(slam) => *slam,
+        Err(_) => return false,
+    };
+    if slam.ready_in > 0 {
+        return false;
+    }
+    if DistanceAlg::Pythagoras.distance2d(chaser_pos, player_pos) > slam.radius as f32 + 0.5 {
+        return false;
+    }
+
+    // The slam hits the player wherever they stand in reach, leaving them dazed
+    let player = <Entity>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .copied()
+        .next()
+        .unwrap();
+    let name = entry
+        .get_component::<Name>()
+        .map_or("monster".to_string(), |name| name.0.clone());
+    log.add(format!("The {} slams the ground!", name));
+    commands.push((
+        (),
+        WantsToAttack {
+            attacker: chaser,
+            victim: player,
+        },
+    ));
+    afflict(
+        ecs,
+        commands,
+        StatusEffect {
+            target: player,
+            kind: StatusEffectKind::Confusion,
+            remaining_turns: 2,
+            magnitude: 5,
+        },
+    );
+    commands.add_component(
+        chaser,
+        GroundSlam {
+            ready_in: slam.cooldown,
+            ..slam
+        },
+    );
+    true
+}
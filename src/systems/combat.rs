@@ -1,21 +1,66 @@
+use super::status_effects::afflict;
 use crate::prelude::*;
 
+// The volume of the sound made by a fight
+const COMBAT_VOLUME: i32 = 12;
+
 #[system]
 #[read_component(WantsToAttack)]
 #[read_component(Player)]
+#[read_component(RangedAttack)]
+#[read_component(Point)]
+#[read_component(AppliesEffect)]
+#[read_component(Damage)]
+#[read_component(Equipped)]
+#[read_component(StatBonus)]
+#[read_component(LootTable)]
+#[read_component(Name)]
+#[read_component(StatusEffect)]
 #[write_component(Health)]
-pub fn combat(ecs: &mut SubWorld, commands: &mut CommandBuffer) {
+pub fn combat(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] rng: &mut RandomNumberGenerator,
+    #[resource] log: &mut GameLog,
+    #[resource] stats: &mut RunStats,
+) {
     // Get the query of entities that want to attack
     let mut attackers = <(Entity, &WantsToAttack)>::query();
 
     // Gather the attackers and victims into a collection
-    let victims: Vec<(Entity, Entity)> = attackers
+    let victims: Vec<(Entity, Entity, Entity)> = attackers
         .iter(ecs)
-        .map(|(entity, attack)| (*entity, attack.victim))
+        .map(|(entity, attack)| (*entity, attack.attacker, attack.victim))
         .collect();
 
     // Iterate the attackers/victims and resolve combat
-    victims.iter().for_each(|(message, victim)| {
+    victims.iter().for_each(|(message, attacker, victim)| {
+        // Determine the damage dealt, using the ranged damage for attackers that fight from afar,
+        // adding the bonus from the attacker's weapon and taking off the victim's armor. Armor can
+        // soften a blow but never turn it aside entirely
+        let base_damage = ecs
+            .entry_ref(*attacker)
+            .ok()
+            .and_then(|entry| {
+                entry
+                    .get_component::<RangedAttack>()
+                    .ok()
+                    .map(|r| r.damage)
+                    .or_else(|| entry.get_component::<Damage>().ok().map(|d| d.0))
+            })
+            .unwrap_or(1);
+        let damage = i32::max(
+            1,
+            base_damage + equipment_bonus(ecs, *attacker).attack
+                - equipment_bonus(ecs, *victim).defense,
+        );
+
+        // Determine any status effect the attacker inflicts
+        let effect = ecs
+            .entry_ref(*attacker)
+            .ok()
+            .and_then(|entry| entry.get_component::<AppliesEffect>().ok().copied());
+
         // Determine if the victim is the player
         let is_player = ecs
             .entry_ref(*victim)
@@ -23,18 +68,127 @@ pub fn combat(ecs: &mut SubWorld, commands: &mut CommandBuffer) {
             .get_component::<Player>()
             .is_ok();
 
-        if let Ok(mut health) = ecs
+        // Describe the blow
+        let attacker_name = name_of(ecs, *attacker);
+        if is_player {
+            log.add(format!("The {} hits you for {}.", attacker_name, damage));
+        } else {
+            log.add(format!(
+                "You hit the {} for {}.",
+                name_of(ecs, *victim),
+                damage
+            ));
+        }
+
+        let mut killed = false;
+        let mut affliction = None;
+        if let Ok(health) = ecs
             .entry_mut(*victim)
             .unwrap()
             .get_component_mut::<Health>()
         {
-            println!("Health before attack: {}", health.current);
-            health.current -= 1;
+            let was_alive = health.current > 0;
+            health.current -= damage;
             if health.current < 1 && !is_player {
                 commands.remove(*victim);
+                killed = was_alive;
+            } else if health.current < 1 {
+                stats.player_killed_by(attacker_name);
+            } else if let Some(effect) = effect {
+                // Afflict the surviving victim with any status effect the attacker inflicts
+                affliction = Some(effect.afflict(*victim));
             }
-            println!("Health after attack: {}", health.current);
+        }
+        if let Some(affliction) = affliction {
+            afflict(ecs, commands, affliction);
+        }
+
+        // Slain monsters drop their loot
+        if killed {
+            log.add(format!("You kill the {}.", name_of(ecs, *victim)));
+            let by_player = ecs
+                .entry_ref(*attacker)
+                .is_ok_and(|entry| entry.get_component::<Player>().is_ok());
+            slay(ecs, commands, rng, *victim, by_player);
+        }
+
+        // The sound of the fight carries to any monsters nearby
+        if let Ok(pos) = ecs.entry_ref(*victim).unwrap().get_component::<Point>() {
+            commands.push((
+                (),
+                Sound {
+                    source: *pos,
+                    volume: COMBAT_VOLUME,
+                },
+            ));
         }
         commands.remove(*message);
     })
 }
+
+// Totals the stat bonuses from the items equipped by an entity
+pub fn equipment_bonus(ecs: &SubWorld, owner: Entity) -> StatBonus {
+    <(&Equipped, &StatBonus)>::query()
+        .iter(ecs)
+        .filter(|(equipped, _)| equipped.owner == owner)
+        .fold(
+            StatBonus {
+                attack: 0,
+                defense: 0,
+            },
+            |total, (_, bonus)| StatBonus {
+                attack: total.attack + bonus.attack,
+                defense: total.defense + bonus.defense,
+            },
+        )
+}
+
+// Handles the death of a slain monster, dropping its loot where it fell. Only the kills the player
+// made are reported, for the experience and statistics
+pub fn slay(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    rng: &mut RandomNumberGenerator,
+    monster: Entity,
+    by_player: bool,
+) {
+    let entry = ecs.entry_ref(monster).unwrap();
+    if let (Ok(table), Ok(pos)) = (
+        entry.get_component::<LootTable>(),
+        entry.get_component::<Point>(),
+    ) {
+        if let Some(loot) = table.roll(rng).flatten() {
+            spawn_loot(commands, rng, *pos, loot);
+        }
+    }
+
+    if !by_player {
+        return;
+    }
+
+    // Tougher monsters are worth more experience
+    let xp = entry
+        .get_component::<Health>()
+        .map(|health| health.max * 5)
+        .unwrap_or(0);
+    commands.push((
+        (),
+        Slain {
+            name: name_of(ecs, monster),
+            xp,
+        },
+    ));
+}
+
+// Gets the name of an entity, for describing what it's up to
+pub fn name_of(ecs: &SubWorld, entity: Entity) -> String {
+    ecs.entry_ref(entity)
+        .ok()
+        .and_then(|entry| {
+            entry
+                .get_component::<Name>()
+                .ok()
+                .map(|name| name.0.clone())
+        })
+        .unwrap_or_else(|| "something".to_string())
+}
@@ -0,0 +1,13 @@
+use crate::prelude::*;
+
+// A system that recovers the monsters' special attacks a little more each turn, whether or not they
+// can see the player
+#[system]
+#[write_component(GroundSlam)]
+pub fn cooldowns(ecs: &mut SubWorld) {
+    let mut slams = <&mut GroundSlam>::query();
+    slams
+        .iter_mut(ecs)
+        .filter(|slam| slam.ready_in > 0)
+        .for_each(|slam| slam.ready_in -= 1);
+}
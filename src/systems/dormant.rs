@@ -0,0 +1,29 @@
+use super::alert::{raise_alert, wake};
+use crate::prelude::*;
+
+// A system that rouses dormant monsters once they spot the player
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(FieldOfView)]
+#[read_component(Dormant)]
+#[read_component(MovingRandomly)]
+#[read_component(Name)]
+#[read_component(Pack)]
+pub fn dormant(ecs: &SubWorld, commands: &mut CommandBuffer, #[resource] log: &mut GameLog) {
+    // Find the player position
+    let mut players = <&Point>::query().filter(component::<Player>());
+    let player_pos = *players.iter(ecs).next().unwrap();
+
+    // A sleeper that rises also alerts its allies, as a random mover does when it spots the player
+    let mut sleepers =
+        <(Entity, &Point, &FieldOfView, &Name)>::query().filter(component::<Dormant>());
+    sleepers
+        .iter(ecs)
+        .filter(|(_, _, fov, _)| fov.visible_tiles.contains(&player_pos))
+        .for_each(|(entity, pos, _, name)| {
+            log.add(format!("The {} rises to face you!", name.0));
+            wake(*entity, player_pos, ecs, commands);
+            raise_alert(*entity, *pos, player_pos, ecs, commands);
+        });
+}
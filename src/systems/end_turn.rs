@@ -1,15 +1,32 @@
+use super::status_effects::find_status_effect;
 use crate::prelude::*;
 
+// The number of turns between each point of damage a starving entity takes
+const STARVATION_INTERVAL: i32 = 5;
+
 // A system that handles state change between turns
 #[system]
-#[read_component(Health)]
 #[read_component(Point)]
 #[read_component(Player)]
 #[read_component(AmuletOfYala)]
-pub fn end_turn(ecs: &SubWorld, #[resource] turn_state: &mut TurnState) {
-    // Get the Amulet position
-    let mut amulet_query = <&Point>::query().filter(component::<AmuletOfYala>());
-    let amulet_pos = amulet_query.iter(ecs).next().unwrap();
+#[read_component(Carried)]
+#[read_component(StatusEffect)]
+#[write_component(Health)]
+#[write_component(Hunger)]
+pub fn end_turn(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] log: &mut GameLog,
+    #[resource] stats: &mut RunStats,
+    #[resource] map: &Map,
+    #[resource] goal: &Goal,
+) {
+    // Find out if the player is carrying the Amulet, which is only found on the final level
+    let mut players = <Entity>::query().filter(component::<Player>());
+    let player = *players.iter(ecs).next().unwrap();
+    let mut amulet_query = <&Carried>::query().filter(component::<AmuletOfYala>());
+    let has_amulet = amulet_query.iter(ecs).any(|carried| carried.0 == player);
 
     // Figure out the next turn state
     let mut new_state = match turn_state {
@@ -19,6 +36,38 @@ pub fn end_turn(ecs: &SubWorld, #[resource] turn_state: &mut TurnState) {
         _ => *turn_state,
     };
 
+    // A hasted player gets extra turns before the monsters get to act
+    if *turn_state == TurnState::PlayerTurn {
+        stats.turns += 1;
+
+        // The player grows hungrier with every turn, and starts wasting away once starving
+        let mut player_entry = ecs.entry_mut(player).unwrap();
+        let starving = if let Ok(hunger) = player_entry.get_component_mut::<Hunger>() {
+            let before = hunger.state();
+            hunger.turns -= 1;
+            if hunger.state() != before {
+                log.add(format!("You are {}.", hunger.state().name().to_lowercase()));
+            }
+            hunger.turns <= 0 && hunger.turns % STARVATION_INTERVAL == 0
+        } else {
+            false
+        };
+        if starving {
+            if let Ok(health) = player_entry.get_component_mut::<Health>() {
+                health.current -= 1;
+                if health.current < 1 {
+                    stats.player_killed_by("Starvation");
+                }
+            }
+        }
+
+        if let Some(haste) = find_status_effect(ecs, player, StatusEffectKind::Haste) {
+            if haste.remaining_turns % (haste.magnitude + 1) != 0 {
+                new_state = TurnState::AwaitingInput;
+            }
+        }
+    }
+
     // Iterate over player health and position, and select the game state appropriately
     let mut player_query = <(&Health, &Point)>::query().filter(component::<Player>());
     player_query
@@ -29,10 +78,34 @@ pub fn end_turn(ecs: &SubWorld, #[resource] turn_state: &mut TurnState) {
                 new_state = TurnState::GameOver;
             }
 
-            // If the player intersects the amulet, it's victory
-            if player_pos == amulet_pos {
+            // If the player has claimed the amulet, it's victory. When they have to escape with it,
+            // taking it wakes the whole level and starts the climb back to the surface instead
+            if has_amulet && *goal == Goal::ClaimAmulet {
                 new_state = TurnState::Victory;
             }
+            if has_amulet && *goal == Goal::EscapeWithAmulet && !stats.returning {
+                stats.returning = true;
+                log.add("The dungeon shudders as you lift the amulet. Now get out alive!");
+                commands.push((
+                    (),
+                    Alert {
+                        source: *player_pos,
+                        target: *player_pos,
+                        radius: WHOLE_LEVEL_RADIUS,
+                        pack: None,
+                    },
+                ));
+            }
+
+            // If the player reaches the stairs, they go down to the next level, or back up towards
+            // the surface as long as they still have the amulet
+            match map.tiles[map_idx(player_pos.x, player_pos.y)] {
+                TileType::Exit => new_state = TurnState::NextLevel,
+                TileType::UpStairs if stats.returning && has_amulet => {
+                    new_state = TurnState::PreviousLevel
+                }
+                _ => {}
+            }
         });
 
     // Update the turn state
@@ -6,7 +6,13 @@ use crate::prelude::*;
 #[read_component(Render)]
 #[read_component(FieldOfView)]
 #[read_component(Player)]
-pub fn entity_render(ecs: &SubWorld, #[resource] viewport: &Viewport) {
+#[read_component(Projectile)]
+#[read_component(Hidden)]
+pub fn entity_render(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] viewport: &Viewport,
+) {
     // Create a new draw batch
     let mut draw_batch = DrawBatch::new();
 
@@ -20,9 +26,9 @@ pub fn entity_render(ecs: &SubWorld, #[resource] viewport: &Viewport) {
     let mut player_fov_query = <&FieldOfView>::query().filter(component::<Player>());
     let player_fov = player_fov_query.iter(ecs).next().unwrap();
 
-    // Query for all renderable entities (having Point and Render facets),
+    // Query for all renderable entities (having Point and Render facets) that haven't been hidden,
     // filter only those visible to the player, and render them
-    let mut renderable_query = <(&Point, &Render)>::query();
+    let mut renderable_query = <(&Point, &Render)>::query().filter(!component::<Hidden>());
     renderable_query
         .iter(ecs)
         .filter(|(pos, _)| player_fov.visible_tiles.contains(pos))
@@ -30,6 +36,19 @@ pub fn entity_render(ecs: &SubWorld, #[resource] viewport: &Viewport) {
             draw_batch.set(*pos - offset, render.color, render.glyph);
         });
 
+    // Draw any projectiles in flight, and remove them so that they only appear for a single frame
+    let mut projectiles = <(Entity, &Projectile)>::query();
+    projectiles.iter(ecs).for_each(|(entity, projectile)| {
+        projectile
+            .path
+            .iter()
+            .filter(|pos| player_fov.visible_tiles.contains(pos))
+            .for_each(|pos| {
+                draw_batch.set(*pos - offset, ColorPair::new(ORANGE, BLACK), to_cp437('*'));
+            });
+        commands.remove(*entity);
+    });
+
     // Submit the batch to be rendered, well after the background
     draw_batch.submit(5000).expect("Batch error");
 }
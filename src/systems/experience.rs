@@ -0,0 +1,39 @@
+use crate::prelude::*;
+
+// The health the player's maximum grows by with each level gained
+const HEALTH_PER_LEVEL: i32 = 2;
+
+// A system that counts the monsters slain, and rewards the player with experience for them,
+// levelling them up once they have gained enough
+#[system]
+#[read_component(Slain)]
+#[read_component(Player)]
+#[write_component(Experience)]
+#[write_component(Health)]
+pub fn experience(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] stats: &mut RunStats,
+    #[resource] log: &mut GameLog,
+) {
+    let slain: Vec<(Entity, Slain)> = <(Entity, &Slain)>::query()
+        .iter(ecs)
+        .map(|(entity, slain)| (*entity, slain.clone()))
+        .collect();
+
+    let mut players = <(&mut Experience, &mut Health)>::query().filter(component::<Player>());
+    slain.iter().for_each(|(message, slain)| {
+        *stats.kills.entry(slain.name.clone()).or_insert(0) += 1;
+
+        players.iter_mut(ecs).for_each(|(experience, health)| {
+            experience.xp += slain.xp;
+            while experience.xp >= experience.next_level_xp() {
+                experience.level += 1;
+                health.max += HEALTH_PER_LEVEL;
+                health.current = health.max;
+                log.add(format!("Welcome to level {}!", experience.level));
+            }
+        });
+        commands.remove(*message);
+    });
+}
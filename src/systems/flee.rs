@@ -0,0 +1,130 @@
+use crate::prelude::*;
+use std::collections::VecDeque;
+
+// How much further fleeing monsters look beyond their nearest escape when choosing where to run
+const SAFETY_SCALE: f32 = 1.2;
+
+// A system that handles wounded monsters fleeing from the player
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(Flees)]
+#[read_component(Fleeing)]
+#[read_component(FieldOfView)]
+#[write_component(Health)]
+pub fn flee(#[resource] map: &Map, ecs: &mut SubWorld, commands: &mut CommandBuffer) {
+    // Find the player position
+    let mut players = <&Point>::query().filter(component::<Player>());
+    let player_pos = *players.iter(ecs).next().unwrap();
+
+    // Create a Dijkstra map to the player in the same way as the chasers, and turn it into a safety
+    // map so that the lowest exit from any tile leads away from the player
+    let player_idx = map_idx(player_pos.x, player_pos.y);
+    let search_targets = vec![player_idx];
+    let mut flee_map = DijkstraMap::new(SCREEN_WIDTH, SCREEN_HEIGHT, &search_targets, map, 1024.0);
+    safety_map(&mut flee_map, map);
+
+    // Gather the monsters that are able to flee
+    let mut fleers = <(Entity, &Point, &Health, &Flees, &FieldOfView)>::query();
+    let fleers: Vec<(Entity, Point, Health, Flees, bool)> = fleers
+        .iter(ecs)
+        .map(|(entity, pos, health, flees, fov)| {
+            (
+                *entity,
+                *pos,
+                *health,
+                *flees,
+                fov.visible_tiles.contains(&player_pos),
+            )
+        })
+        .collect();
+
+    fleers
+        .iter()
+        .for_each(|(entity, pos, health, flees, can_see_player)| {
+            let is_fleeing = ecs
+                .entry_ref(*entity)
+                .unwrap()
+                .get_component::<Fleeing>()
+                .is_ok();
+
+            // If the monster has recovered, go back to chasing the player
+            if health.current >= flees.threshold {
+                if is_fleeing {
+                    stop_fleeing(*entity, commands);
+                }
+                return;
+            }
+
+            // If the monster is out of sight of the player, catch its breath and recover some health
+            if !*can_see_player {
+                if is_fleeing {
+                    if let Ok(health) = ecs
+                        .entry_mut(*entity)
+                        .unwrap()
+                        .get_component_mut::<Health>()
+                    {
+                        health.current = i32::min(health.max, health.current + 1);
+                    }
+                }
+                return;
+            }
+
+            // Find the exit that leads furthest away from the player. If there isn't one that
+            // improves on the current tile, the monster is cornered and turns to fight
+            let idx = map_idx(pos.x, pos.y);
+            let destination = DijkstraMap::find_lowest_exit(&flee_map, idx, map)
+                .filter(|exit| flee_map.map[*exit] < flee_map.map[idx])
+                .map(|exit| map.index_to_point2d(exit));
+            match destination {
+                Some(destination) => {
+                    if !is_fleeing {
+                        commands.remove_component::<ChasingPlayer>(*entity);
+                        commands.add_component(*entity, Fleeing);
+                    }
+                    commands.push((
+                        (),
+                        WantsToMove {
+                            entity: *entity,
+                            destination,
+                        },
+                    ));
+                }
+                None => {
+                    if is_fleeing {
+                        stop_fleeing(*entity, commands);
+                    }
+                }
+            }
+        });
+}
+
+// Switches a fleeing monster back to chasing the player
+fn stop_fleeing(entity: Entity, commands: &mut CommandBuffer) {
+    commands.remove_component::<Fleeing>(entity);
+    commands.add_component(entity, ChasingPlayer);
+}
+
+// Turns a Dijkstra map to the player into one leading away from them. Simply inverting the distances
+// sends a fleeing monster into the nearest dead end, so they are scaled up a little and then spread
+// back out over the map, letting the way past the player to open ground beat a corner close by
+fn safety_map(flee_map: &mut DijkstraMap, map: &Map) {
+    flee_map
+        .map
+        .iter_mut()
+        .filter(|distance| **distance < f32::MAX)
+        .for_each(|distance| *distance *= -SAFETY_SCALE);
+
+    let mut open: VecDeque<usize> = (0..flee_map.map.len())
+        .filter(|idx| flee_map.map[*idx] < f32::MAX)
+        .collect();
+    while let Some(idx) = open.pop_front() {
+        for (exit, cost) in map.get_available_exits(idx) {
+            let distance = flee_map.map[idx] + cost;
+            if distance < flee_map.map[exit] {
+                flee_map.map[exit] = distance;
+                open.push_back(exit);
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::alert::wake;
+use crate::prelude::*;
+
+// A system that propagates sounds over the map, sending the monsters that hear them to investigate
+#[system]
+#[read_component(Sound)]
+#[read_component(Point)]
+#[read_component(Enemy)]
+#[read_component(Player)]
+#[read_component(FieldOfView)]
+#[read_component(MovingRandomly)]
+#[read_component(Dormant)]
+pub fn hearing(#[resource] map: &Map, ecs: &SubWorld, commands: &mut CommandBuffer) {
+    // Find the player position
+    let mut players = <&Point>::query().filter(component::<Player>());
+    let player_pos = *players.iter(ecs).next().unwrap();
+
+    let mut sounds = <(Entity, &Sound)>::query();
+    let mut listeners = <(Entity, &Point, &FieldOfView)>::query().filter(component::<Enemy>());
+    sounds.iter(ecs).for_each(|(message, sound)| {
+        // Flood the sound out over the map, as far as its volume carries it
+        let source_idx = map_idx(sound.source.x, sound.source.y);
+        let sound_map = DijkstraMap::new(
+            SCREEN_WIDTH,
+            SCREEN_HEIGHT,
+            &[source_idx],
+            &Acoustics(map),
+            sound.volume as f32,
+        );
+
+        // Any monster that hears the sound, and can't already see the player, goes to investigate
+        listeners
+            .iter(ecs)
+            .filter(|(_, pos, fov)| {
+                sound_map.map[map_idx(pos.x, pos.y)] < f32::MAX
+                    && !fov.visible_tiles.contains(&player_pos)
+            })
+            .for_each(|(entity, _, _)| wake(*entity, sound.source, ecs, commands));
+
+        commands.remove(*message);
+    });
+}
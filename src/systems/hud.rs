@@ -3,10 +3,18 @@ use crate::prelude::*;
 #[system]
 #[read_component(Health)]
 #[read_component(Player)]
-pub fn hud(ecs: &SubWorld) {
+#[read_component(StatusEffect)]
+#[read_component(Item)]
+#[read_component(Carried)]
+#[read_component(Name)]
+#[read_component(Equipped)]
+#[read_component(Gold)]
+#[read_component(Hunger)]
+#[read_component(Mana)]
+pub fn hud(ecs: &SubWorld, #[resource] stats: &RunStats) {
     // Get the player health
-    let mut player_healths = <&Health>::query().filter(component::<Player>());
-    let player_health = player_healths.iter(ecs).next().unwrap();
+    let mut player_healths = <(Entity, &Health)>::query().filter(component::<Player>());
+    let (player, player_health) = player_healths.iter(ecs).next().unwrap();
 
     // Draw the health bar
     let mut draw_batch = DrawBatch::new();
@@ -25,7 +33,97 @@ pub fn hud(ecs: &SubWorld) {
     );
 
     // Draw the instructions
-    draw_batch.print_centered(1, "Explore the dungeon. Cursor keys to move.");
+    draw_batch.print_centered(
+        1,
+        "Explore the dungeon. Cursor keys to move. G to pick up, I for inventory. Escape for the menu and controls.",
+    );
+
+    // Draw the player's gold
+    if let Ok(gold) = ecs.entry_ref(*player).unwrap().get_component::<Gold>() {
+        draw_batch.print_color_right(
+            Point::new(SCREEN_WIDTH * 2 - 1, 1),
+            format!("Gold: {}", gold.0),
+            ColorPair::new(GOLD, BLACK),
+        );
+    }
+
+    // Draw the player's mana, if they have any to cast spells with
+    if let Ok(mana) = ecs.entry_ref(*player).unwrap().get_component::<Mana>() {
+        if mana.max > 0 {
+            draw_batch.print_color_right(
+                Point::new(SCREEN_WIDTH * 2 - 1, 2),
+                format!("Mana: {} / {}", mana.current, mana.max),
+                ColorPair::new(CYAN, BLACK),
+            );
+        }
+    }
+
+    // Draw how deep the player is, and remind them to get out once they have the amulet
+    if stats.returning {
+        draw_batch.print_color_right(
+            Point::new(SCREEN_WIDTH * 2 - 1, 3),
+            format!("Escape to the surface! Depth: {}", stats.depth),
+            ColorPair::new(GOLD, BLACK),
+        );
+    } else {
+        draw_batch.print_color_right(
+            Point::new(SCREEN_WIDTH * 2 - 1, 3),
+            format!("Depth: {} / {}", stats.depth, FINAL_DEPTH),
+            ColorPair::new(WHITE, BLACK),
+        );
+    }
+
+    // Draw how hungry the player is
+    if let Ok(hunger) = ecs.entry_ref(*player).unwrap().get_component::<Hunger>() {
+        let color = match hunger.state() {
+            HungerState::WellFed => GREEN,
+            HungerState::Normal => WHITE,
+            HungerState::Hungry => ORANGE,
+            HungerState::Starving => RED,
+        };
+        draw_batch.print_color(
+            Point::new(1, 1),
+            hunger.state().name(),
+            ColorPair::new(color, BLACK),
+        );
+    }
+
+    // Draw the status effects afflicting the player
+    let mut effects = <&StatusEffect>::query();
+    let effects: Vec<String> = effects
+        .iter(ecs)
+        .filter(|effect| effect.target == *player)
+        .map(|effect| format!("{} ({})", effect.kind.name(), effect.remaining_turns))
+        .collect();
+    if !effects.is_empty() {
+        draw_batch.print_color_centered(2, effects.join("  "), ColorPair::new(ORANGE, BLACK));
+    }
+
+    // Draw the player's inventory
+    let mut items = <(&Carried, &Name, Option<&Equipped>)>::query().filter(component::<Item>());
+    let mut y = 3;
+    items
+        .iter(ecs)
+        .filter(|(carried, _, _)| carried.0 == *player)
+        .for_each(|(_, name, equipped)| {
+            let suffix = if equipped.is_some() {
+                " (equipped)"
+            } else {
+                ""
+            };
+            draw_batch.print(
+                Point::new(3, y),
+                format!("{} : {}{}", y - 2, &name.0, suffix),
+            );
+            y += 1;
+        });
+    if y > 3 {
+        draw_batch.print_color(
+            Point::new(3, 2),
+            "Items carried",
+            ColorPair::new(YELLOW, BLACK),
+        );
+    }
 
     draw_batch.submit(10000).expect("Batch error");
 }
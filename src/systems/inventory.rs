@@ -0,0 +1,104 @@
+use crate::prelude::*;
+
+// A system that handles the inventory screen, from which carried items can be equipped, unequipped
+// and used
+#[system]
+#[read_component(Player)]
+#[read_component(Item)]
+#[read_component(AmuletOfYala)]
+#[read_component(Carried)]
+#[read_component(Name)]
+#[read_component(Equippable)]
+#[read_component(Equipped)]
+#[read_component(StatBonus)]
+pub fn inventory(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+) {
+    // Get the player
+    let mut players = <Entity>::query().filter(component::<Player>());
+    let player = *players.iter(ecs).next().unwrap();
+
+    // Gather the items carried by the player
+    let mut items = <(Entity, &Carried, &Name)>::query().filter(component::<Item>());
+    let items: Vec<(Entity, String)> = items
+        .iter(ecs)
+        .filter(|(_, carried, _)| carried.0 == player)
+        .map(|(entity, _, name)| (*entity, name.0.clone()))
+        .collect();
+
+    // Draw the inventory screen
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(2);
+    let top = 20;
+    draw_batch.draw_box(
+        Rect::with_size(40, top, 80, items.len() as i32 + 5),
+        ColorPair::new(WHITE, BLACK),
+    );
+    draw_batch.print_color_centered(top, " Inventory ", ColorPair::new(YELLOW, BLACK));
+    if items.is_empty() {
+        draw_batch.print(Point::new(42, top + 2), "You aren't carrying anything.");
+    }
+    items.iter().enumerate().for_each(|(i, (item, name))| {
+        let entry = ecs.entry_ref(*item).unwrap();
+        let mut description = format!("{}) {}", (b'a' + i as u8) as char, name);
+        if let Ok(bonus) = entry.get_component::<StatBonus>() {
+            if bonus.attack != 0 {
+                description += &format!(" (+{} attack)", bonus.attack);
+            }
+            if bonus.defense != 0 {
+                description += &format!(" (+{} defense)", bonus.defense);
+            }
+        }
+        let color = if let Ok(equipped) = entry.get_component::<Equipped>() {
+            description += match equipped.slot {
+                EquipmentSlot::Weapon => " [wielded]",
+                EquipmentSlot::Armor => " [worn]",
+            };
+            GREEN
+        } else {
+            WHITE
+        };
+        draw_batch.print_color(
+            Point::new(42, top + 2 + i as i32),
+            description,
+            ColorPair::new(color, BLACK),
+        );
+    });
+    draw_batch.print_color_centered(
+        top + items.len() as i32 + 4,
+        " Letter to equip, unequip or use an item. Escape to close. ",
+        ColorPair::new(YELLOW, BLACK),
+    );
+    draw_batch.submit(11000).expect("Batch error");
+
+    // Handle the input
+    if let Some(key) = *key {
+        match key {
+            VirtualKeyCode::Escape | VirtualKeyCode::I => *turn_state = TurnState::AwaitingInput,
+            _ => {
+                // The amulet is listed, but it can't be used
+                let selection = letter_to_option(key);
+                let usable = |item: Entity| {
+                    ecs.entry_ref(item)
+                        .is_ok_and(|entry| entry.get_component::<AmuletOfYala>().is_err())
+                };
+                if selection >= 0
+                    && (selection as usize) < items.len()
+                    && usable(items[selection as usize].0)
+                {
+                    commands.push((
+                        (),
+                        ActivateItem {
+                            used_by: player,
+                            item: items[selection as usize].0,
+                        },
+                    ));
+                    *turn_state = TurnState::PlayerTurn;
+                }
+            }
+        }
+    }
+}
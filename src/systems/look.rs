@@ -0,0 +1,157 @@
+use crate::prelude::*;
+
+// A system that handles looking around, moving a cursor freely over the map and describing the tile
+// beneath it, along with anything the player can see there
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(Name)]
+#[read_component(Health)]
+#[read_component(FieldOfView)]
+#[read_component(Hidden)]
+#[read_component(Item)]
+#[read_component(Enemy)]
+#[read_component(Fleeing)]
+#[read_component(MovingRandomly)]
+#[read_component(ChasingPlayer)]
+#[read_component(Investigating)]
+#[read_component(Dormant)]
+#[read_component(StatusEffect)]
+#[allow(clippy::borrowed_box)]
+pub fn look(
+    ecs: &SubWorld,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] look: &mut Look,
+    #[resource] map: &Map,
+    #[resource] viewport: &mut Viewport,
+    #[resource] theme: &Box<dyn MapTheme>,
+) {
+    // Get the player
+    let mut players = <(&Point, &FieldOfView)>::query().filter(component::<Player>());
+    let (player_pos, fov) = players.iter(ecs).next().unwrap();
+
+    // Handle the input, moving the cursor and the view along with it, or going back to the game
+    if let Some(key) = *key {
+        let delta = match key {
+            VirtualKeyCode::Left => Point::new(-1, 0),
+            VirtualKeyCode::Right => Point::new(1, 0),
+            VirtualKeyCode::Up => Point::new(0, -1),
+            VirtualKeyCode::Down => Point::new(0, 1),
+            _ => Point::zero(),
+        };
+        if map.in_bounds(look.cursor + delta) {
+            look.cursor += delta;
+            viewport.follow(look.cursor);
+        }
+
+        if let VirtualKeyCode::Escape | VirtualKeyCode::L = key {
+            viewport.follow(*player_pos);
+            *turn_state = TurnState::AwaitingInput;
+            return;
+        }
+    }
+
+    // Draw the cursor over the map
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(1);
+    let offset = Point::new(viewport.left_x, viewport.top_y);
+    draw_batch.set(
+        look.cursor - offset,
+        ColorPair::new(CYAN, BLACK),
+        to_cp437('X'),
+    );
+    draw_batch.submit(6000).expect("Batch error");
+
+    // Describe the tile, and whether the player can see it now or only remembers it
+    let idx = map_idx(look.cursor.x, look.cursor.y);
+    let visible = fov.visible_tiles.contains(&look.cursor);
+    let mut lines = Vec::new();
+    if map.revealed_tiles[idx] {
+        let seen = if visible { "in view" } else { "remembered" };
+        lines.push((
+            format!("{} ({})", theme.describe(map.tiles[idx]), seen),
+            WHITE,
+        ));
+    } else {
+        lines.push(("Unexplored".to_string(), GREY));
+    }
+
+    // Describe what's there, which the player can only tell while they can see it
+    if visible {
+        <(Entity, &Point, &Name)>::query()
+            .filter(!component::<Hidden>())
+            .iter(ecs)
+            .filter(|(_, pos, _)| **pos == look.cursor)
+            .for_each(|(entity, _, name)| {
+                lines.push((name.0.clone(), YELLOW));
+                lines.extend(describe(ecs, *entity, *player_pos));
+            });
+    }
+
+    // Draw the descriptions, along with how to get out of look mode
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(2);
+    let top = SCREEN_HEIGHT * 2 - 4 - lines.len() as i32;
+    lines.iter().enumerate().for_each(|(i, (line, color))| {
+        draw_batch.print_color(
+            Point::new(2, top + i as i32),
+            line,
+            ColorPair::new(*color, BLACK),
+        );
+    });
+    draw_batch.print_color_centered(
+        SCREEN_HEIGHT * 2 - 2,
+        " Looking: cursor keys to move, Escape to stop. ",
+        ColorPair::new(YELLOW, BLACK),
+    );
+    draw_batch.submit(11000).expect("Batch error");
+}
+
+// Describes the health, behavior and status effects of a creature
+fn describe(ecs: &SubWorld, entity: Entity, player_pos: Point) -> Vec<(String, (u8, u8, u8))> {
+    let entry = ecs.entry_ref(entity).unwrap();
+    let mut lines = Vec::new();
+    if let Ok(health) = entry.get_component::<Health>() {
+        lines.push((
+            format!("  Health: {} / {}", health.current, health.max),
+            WHITE,
+        ));
+    }
+
+    if entry.get_component::<Enemy>().is_ok() {
+        let sees_player = entry
+            .get_component::<FieldOfView>()
+            .map(|fov| fov.visible_tiles.contains(&player_pos))
+            .unwrap_or(false);
+        let behavior = if entry.get_component::<Fleeing>().is_ok() {
+            "Fleeing"
+        } else if entry.get_component::<MovingRandomly>().is_ok() {
+            "Wandering"
+        } else if entry.get_component::<Dormant>().is_ok() {
+            "Dormant"
+        } else if entry.get_component::<ChasingPlayer>().is_ok() && sees_player {
+            "Hunting you"
+        } else if entry.get_component::<Investigating>().is_ok() {
+            "Searching"
+        } else {
+            "Unaware of you"
+        };
+        lines.push((format!("  {}", behavior), ORANGE));
+    }
+
+    <&StatusEffect>::query()
+        .iter(ecs)
+        .filter(|effect| effect.target == entity)
+        .for_each(|effect| {
+            lines.push((
+                format!(
+                    "  {} ({} turns)",
+                    effect.kind.name(),
+                    effect.remaining_turns
+                ),
+                ORANGE,
+            ));
+        });
+    lines
+}
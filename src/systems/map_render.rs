@@ -2,6 +2,7 @@ use crate::prelude::*;
 
 // A system that handles the rendering of the map
 #[system]
+#[allow(clippy::borrowed_box)]
 #[read_component(FieldOfView)]
 #[read_component(Player)]
 pub fn map_render(
@@ -0,0 +1,95 @@
+use crate::prelude::*;
+
+// The number of map tiles, in each direction, squeezed into a single character of the minimap
+const SCALE: i32 = 2;
+const MINIMAP_WIDTH: i32 = SCREEN_WIDTH / SCALE;
+const MINIMAP_HEIGHT: i32 = SCREEN_HEIGHT / SCALE;
+
+// A system that draws a minimap of the tiles revealed so far in the corner of the HUD, marking the
+// player, the stairs and the amulet once they have been seen and any monsters in view
+#[system]
+#[read_component(Player)]
+#[read_component(Enemy)]
+#[read_component(AmuletOfYala)]
+#[read_component(Point)]
+#[read_component(FieldOfView)]
+pub fn minimap(ecs: &SubWorld, #[resource] map: &Map, #[resource] minimap: &Minimap) {
+    if !minimap.visible {
+        return;
+    }
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(2);
+    let left = SCREEN_WIDTH * 2 - MINIMAP_WIDTH - 1;
+    let top = 5;
+    draw_batch.draw_box(
+        Rect::with_size(left - 1, top - 1, MINIMAP_WIDTH + 1, MINIMAP_HEIGHT + 1),
+        ColorPair::new(GREY, BLACK),
+    );
+    draw_batch.print_color(
+        Point::new(left + 1, top - 1),
+        " Map ",
+        ColorPair::new(YELLOW, BLACK),
+    );
+
+    // Draw the revealed tiles, showing a floor wherever any of the tiles squeezed together is one
+    for y in 0..MINIMAP_HEIGHT {
+        for x in 0..MINIMAP_WIDTH {
+            let mut glyph = None;
+            for ty in y * SCALE..(y + 1) * SCALE {
+                for tx in x * SCALE..(x + 1) * SCALE {
+                    let idx = map_idx(tx, ty);
+                    if map.revealed_tiles[idx] {
+                        match map.tiles[idx] {
+                            TileType::Exit => glyph = Some('>'),
+                            TileType::UpStairs => glyph = Some('<'),
+                            TileType::Floor if glyph != Some('>') && glyph != Some('<') => {
+                                glyph = Some('.')
+                            }
+                            TileType::Floor => {}
+                            TileType::Wall => glyph = glyph.or(Some('#')),
+                        }
+                    }
+                }
+            }
+            if let Some(glyph) = glyph {
+                let color = if glyph == '>' || glyph == '<' {
+                    CYAN
+                } else {
+                    GREY
+                };
+                draw_batch.set(
+                    Point::new(left + x, top + y),
+                    ColorPair::new(color, BLACK),
+                    to_cp437(glyph),
+                );
+            }
+        }
+    }
+
+    // Mark the amulet, the monsters in view and the player, the latter drawn over the others
+    let to_minimap = |pos: &Point| Point::new(left + pos.x / SCALE, top + pos.y / SCALE);
+    <&Point>::query()
+        .filter(component::<AmuletOfYala>())
+        .iter(ecs)
+        .filter(|pos| map.revealed_tiles[map_idx(pos.x, pos.y)])
+        .for_each(|pos| {
+            draw_batch.set(to_minimap(pos), ColorPair::new(GOLD, BLACK), to_cp437('/'));
+        });
+    let mut players = <(&Point, &FieldOfView)>::query().filter(component::<Player>());
+    let (player_pos, fov) = players.iter(ecs).next().unwrap();
+    <&Point>::query()
+        .filter(component::<Enemy>())
+        .iter(ecs)
+        .filter(|pos| fov.visible_tiles.contains(pos))
+        .for_each(|pos| {
+            draw_batch.set(to_minimap(pos), ColorPair::new(RED, BLACK), to_cp437('m'));
+        });
+    draw_batch.set(
+        to_minimap(player_pos),
+        ColorPair::new(YELLOW, BLACK),
+        to_cp437('@'),
+    );
+
+    draw_batch.submit(10500).expect("Batch error");
+}
@@ -1,16 +1,32 @@
 use crate::prelude::*;
 
+mod alert;
+mod character_sheet;
 mod chasing;
 mod combat;
+mod cooldowns;
+mod dormant;
 mod end_turn;
 mod entity_render;
+mod experience;
+mod flee;
 mod fov;
+mod hearing;
 mod hud;
+mod inventory;
+mod look;
 mod map_render;
+mod minimap;
 mod movement;
 mod player_input;
 mod random_move;
+mod shop;
+mod spells;
+mod status_effects;
+mod targeting;
 mod tooltips;
+mod trap_sense;
+mod use_items;
 
 // Creates a schedule that handles input
 pub fn build_input_scheduler() -> Schedule {
@@ -18,25 +34,88 @@ pub fn build_input_scheduler() -> Schedule {
         .add_system(player_input::player_input_system())
         .add_system(fov::fov_system())
         .flush()
+        .add_system(trap_sense::trap_sense_system())
+        .flush()
         .add_system(map_render::map_render_system())
         .add_system(entity_render::entity_render_system())
         .add_system(hud::hud_system())
+        .add_system(minimap::minimap_system())
         .add_system(tooltips::tooltips_system())
         .build()
 }
 
+// Creates a schedule that handles the inventory screen
+pub fn build_inventory_scheduler() -> Schedule {
+    Schedule::builder()
+        .add_system(inventory::inventory_system())
+        .add_system(map_render::map_render_system())
+        .add_system(entity_render::entity_render_system())
+        .add_system(hud::hud_system())
+        .build()
+}
+
+// Creates a schedule that handles the character sheet
+pub fn build_character_sheet_scheduler() -> Schedule {
+    Schedule::builder()
+        .add_system(character_sheet::character_sheet_system())
+        .add_system(map_render::map_render_system())
+        .add_system(entity_render::entity_render_system())
+        .add_system(hud::hud_system())
+        .build()
+}
+
+// Creates a schedule that handles the shop screen
+pub fn build_shop_scheduler() -> Schedule {
+    Schedule::builder()
+        .add_system(shop::shop_system())
+        .add_system(map_render::map_render_system())
+        .add_system(entity_render::entity_render_system())
+        .add_system(hud::hud_system())
+        .build()
+}
+
+// Creates a schedule that handles aiming a spell
+pub fn build_targeting_scheduler() -> Schedule {
+    Schedule::builder()
+        .add_system(targeting::targeting_system())
+        .add_system(map_render::map_render_system())
+        .add_system(entity_render::entity_render_system())
+        .add_system(hud::hud_system())
+        .add_system(minimap::minimap_system())
+        .build()
+}
+
+// Creates a schedule that handles looking around the map
+pub fn build_look_scheduler() -> Schedule {
+    Schedule::builder()
+        .add_system(look::look_system())
+        .add_system(map_render::map_render_system())
+        .add_system(entity_render::entity_render_system())
+        .add_system(hud::hud_system())
+        .build()
+}
+
 // Creates a schedule that handles player movement
 pub fn build_player_scheduler() -> Schedule {
     Schedule::builder()
+        .add_system(use_items::use_items_system())
+        .add_system(spells::spells_system())
         .add_system(combat::combat_system())
         .flush()
+        .add_system(status_effects::status_effects_system())
+        .flush()
         .add_system(movement::movement_system())
+        .add_system(hearing::hearing_system())
         .flush()
         .add_system(fov::fov_system())
         .flush()
+        .add_system(trap_sense::trap_sense_system())
+        .flush()
         .add_system(map_render::map_render_system())
         .add_system(entity_render::entity_render_system())
         .add_system(hud::hud_system())
+        .add_system(minimap::minimap_system())
+        .add_system(experience::experience_system())
         .add_system(end_turn::end_turn_system())
         .build()
 }
@@ -44,18 +123,28 @@ pub fn build_player_scheduler() -> Schedule {
 // Creates a schedule that handles monster movement
 pub fn build_monster_scheduler() -> Schedule {
     Schedule::builder()
+        .add_system(cooldowns::cooldowns_system())
+        .add_system(flee::flee_system())
+        .add_system(dormant::dormant_system())
+        .flush()
         .add_system(random_move::random_move_system())
         .add_system(chasing::chasing_system())
         .flush()
+        .add_system(alert::alert_system())
         .add_system(combat::combat_system())
         .flush()
+        .add_system(status_effects::status_effects_system())
+        .flush()
         .add_system(movement::movement_system())
+        .add_system(hearing::hearing_system())
         .flush()
         .add_system(fov::fov_system())
         .flush()
         .add_system(map_render::map_render_system())
         .add_system(entity_render::entity_render_system())
         .add_system(hud::hud_system())
+        .add_system(minimap::minimap_system())
+        .add_system(experience::experience_system())
         .add_system(end_turn::end_turn_system())
         .build()
 }
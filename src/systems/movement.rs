@@ -1,32 +1,217 @@
+use super::alert::wake;
+use super::combat::{name_of, slay};
+use super::status_effects::afflict;
 use crate::prelude::*;
 
+// The chance, out of 10, that the player notices a hidden trap next to them as they walk by
+const NOTICE_CHANCE: i32 = 3;
+
 // A system that handles the processing of movement messages
 #[system(for_each)]
 #[read_component(Player)]
+#[read_component(Enemy)]
 #[read_component(FieldOfView)]
+#[read_component(Point)]
+#[read_component(Shopkeeper)]
+#[read_component(Trap)]
+#[read_component(Hidden)]
+#[read_component(Damage)]
+#[read_component(AppliesEffect)]
+#[read_component(StatusEffect)]
+#[read_component(MovingRandomly)]
+#[read_component(Dormant)]
+#[read_component(LootTable)]
+#[read_component(Name)]
+#[write_component(Gold)]
+#[write_component(Health)]
+#[allow(clippy::too_many_arguments)]
 pub fn movement(
     entity: &Entity,
     want_move: &WantsToMove,
     #[resource] map: &mut Map,
     #[resource] viewport: &mut Viewport,
+    #[resource] rng: &mut RandomNumberGenerator,
+    #[resource] log: &mut GameLog,
+    #[resource] stats: &mut RunStats,
     ecs: &mut SubWorld,
     commands: &mut CommandBuffer,
 ) {
+    commands.remove(*entity);
+
+    // The mover may have died since deciding to move
+    if ecs.entry_ref(want_move.entity).is_err() {
+        return;
+    }
+
     if map.can_enter_tile(want_move.destination) {
-        commands.add_component(want_move.entity, want_move.destination);
+        // Spring any trap at the destination, which may kill the mover or send them elsewhere
+        let destination = match spring_traps(
+            want_move.entity,
+            want_move.destination,
+            map,
+            rng,
+            log,
+            stats,
+            ecs,
+            commands,
+        ) {
+            Some(destination) => destination,
+            None => return,
+        };
+        commands.add_component(want_move.entity, destination);
+
+        let entry = ecs.entry_ref(want_move.entity).unwrap();
+        let is_player = entry.get_component::<Player>().is_ok();
+        if let Ok(fov) = entry.get_component::<FieldOfView>() {
+            commands.add_component(want_move.entity, fov.clone_dirty());
+
+            if is_player {
+                viewport.follow(destination);
+                fov.visible_tiles.iter().for_each(|pos| {
+                    map.revealed_tiles[map_idx(pos.x, pos.y)] = true;
+                });
+            }
+        }
 
-        if let Ok(entry) = ecs.entry_ref(want_move.entity) {
-            if let Ok(fov) = entry.get_component::<FieldOfView>() {
-                commands.add_component(want_move.entity, fov.clone_dirty());
+        // The player scoops up any gold lying where they step, and may notice traps close by
+        if is_player {
+            collect_gold(ecs, commands, log, want_move.entity, destination);
+            <(Entity, &Point)>::query()
+                .filter(component::<Trap>() & component::<Hidden>())
+                .iter(ecs)
+                .filter(|(_, pos)| DistanceAlg::Pythagoras.distance2d(destination, **pos) < 1.5)
+                .for_each(|(trap, pos)| {
+                    if rng.range(0, 10) < NOTICE_CHANCE {
+                        reveal_trap(*trap, *pos, map, commands);
+                        log.add("You notice a trap.");
+                    }
+                });
+        }
+    }
+}
+
+// Reveals a hidden trap, after which it is drawn on the map and monsters path around it
+pub fn reveal_trap(trap: Entity, pos: Point, map: &mut Map, commands: &mut CommandBuffer) {
+    commands.remove_component::<Hidden>(trap);
+    map.known_traps[map_idx(pos.x, pos.y)] = true;
+}
+
+// Springs the traps at a position on an entity stepping onto it, returning where the entity ends up,
+// or None if the traps killed it
+#[allow(clippy::too_many_arguments)]
+fn spring_traps(
+    victim: Entity,
+    pos: Point,
+    map: &mut Map,
+    rng: &mut RandomNumberGenerator,
+    log: &mut GameLog,
+    stats: &mut RunStats,
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+) -> Option<Point> {
+    let traps: Vec<(Entity, Trap, i32, Option<AppliesEffect>)> =
+        <(Entity, &Point, &Trap, &Damage, Option<&AppliesEffect>)>::query()
+            .iter(ecs)
+            .filter(|(_, trap_pos, _, _, _)| **trap_pos == pos)
+            .map(|(entity, _, trap, damage, effect)| (*entity, *trap, damage.0, effect.copied()))
+            .collect();
 
-                if entry.get_component::<Player>().is_ok() {
-                    viewport.on_player_move(want_move.destination);
-                    fov.visible_tiles.iter().for_each(|pos| {
-                        map.revealed_tiles[map_idx(pos.x, pos.y)] = true;
-                    });
+    // Traps sprung by monsters only give themselves away if the player sees it happen
+    let is_player = ecs
+        .entry_ref(victim)
+        .unwrap()
+        .get_component::<Player>()
+        .is_ok();
+    let seen = is_player
+        || <&FieldOfView>::query()
+            .filter(component::<Player>())
+            .iter(ecs)
+            .any(|fov| fov.visible_tiles.contains(&pos));
+
+    let mut destination = pos;
+    for (trap, Trap { kind }, damage, effect) in traps {
+        if seen {
+            reveal_trap(trap, pos, map, commands);
+            if is_player {
+                log.add(format!("You spring a {}!", name_of(ecs, trap)));
+            } else {
+                log.add(format!(
+                    "The {} springs a {}!",
+                    name_of(ecs, victim),
+                    name_of(ecs, trap)
+                ));
+            }
+        }
+        match kind {
+            TrapKind::SpikePit => {
+                let mut killed = false;
+                let mut affliction = None;
+                if let Ok(health) = ecs.entry_mut(victim).unwrap().get_component_mut::<Health>() {
+                    health.current -= damage;
+                    killed = health.current < 1 && !is_player;
+                    if health.current < 1 && is_player {
+                        stats.player_killed_by(name_of(ecs, trap));
+                    } else if let Some(effect) = effect.filter(|_| health.current > 0) {
+                        // Afflict the surviving victim with any status effect the trap inflicts
+                        affliction = Some(effect.afflict(victim));
+                    }
+                }
+                if let Some(affliction) = affliction {
+                    afflict(ecs, commands, affliction);
                 }
+                if killed {
+                    slay(ecs, commands, rng, victim, false);
+                    commands.remove(victim);
+                    return None;
+                }
+            }
+            TrapKind::Teleport => {
+                // Send the victim to a random open tile that nobody else is standing on
+                let mut occupants = <&Point>::query().filter(component::<Health>());
+                let open: Vec<Point> = map
+                    .tiles
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| **t == TileType::Floor)
+                    .map(|(idx, _)| map.index_to_point2d(idx))
+                    .filter(|pt| !occupants.iter(ecs).any(|occupant| occupant == pt))
+                    .collect();
+                if let Some(pt) = rng.random_slice_entry(&open) {
+                    destination = *pt;
+                }
+            }
+            TrapKind::Alarm => {
+                <Entity>::query()
+                    .filter(component::<Enemy>())
+                    .iter(ecs)
+                    .for_each(|monster| wake(*monster, pos, ecs, commands));
             }
         }
     }
-    commands.remove(*entity);
+    Some(destination)
+}
+
+// Adds any gold piles at a position to the player's purse
+fn collect_gold(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    log: &mut GameLog,
+    player: Entity,
+    pos: Point,
+) {
+    let mut piles = <(Entity, &Point, &Gold)>::query()
+        .filter(!component::<Player>() & !component::<Shopkeeper>());
+    let piles: Vec<(Entity, i32)> = piles
+        .iter(ecs)
+        .filter(|(_, pile_pos, _)| **pile_pos == pos)
+        .map(|(entity, _, gold)| (*entity, gold.0))
+        .collect();
+
+    if let Ok(purse) = ecs.entry_mut(player).unwrap().get_component_mut::<Gold>() {
+        piles.iter().for_each(|(pile, amount)| {
+            purse.0 += amount;
+            commands.remove(*pile);
+            log.add(format!("You pick up {} gold.", amount));
+        });
+    }
 }
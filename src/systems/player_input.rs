@@ -1,40 +1,158 @@
+use super::movement::reveal_trap;
+use super::random_move::random_direction;
+use super::status_effects::find_status_effect;
 use crate::prelude::*;
 
+// The distance around the player that a search reveals traps
+const SEARCH_RADIUS: f32 = 3.0;
+
 // A system that handles player input
 #[system]
 #[read_component(Point)]
 #[read_component(Player)]
 #[read_component(Enemy)]
+#[read_component(Shopkeeper)]
+#[read_component(Item)]
+#[read_component(AmuletOfYala)]
+#[read_component(Carried)]
+#[read_component(StatusEffect)]
+#[read_component(Trap)]
+#[read_component(Hidden)]
+#[read_component(FieldOfView)]
+#[read_component(Hunger)]
+#[read_component(Spellbook)]
+#[read_component(RestHealing)]
+#[read_component(Name)]
+#[read_component(Cleave)]
 #[write_component(Health)]
+#[write_component(Mana)]
+#[allow(clippy::too_many_arguments)]
 pub fn player_input(
     ecs: &mut SubWorld,
     commands: &mut CommandBuffer,
     #[resource] key: &Option<VirtualKeyCode>,
     #[resource] turn_state: &mut TurnState,
+    #[resource] rng: &mut RandomNumberGenerator,
+    #[resource] map: &mut Map,
+    #[resource] targeting: &mut Targeting,
+    #[resource] log: &mut GameLog,
+    #[resource] minimap: &mut Minimap,
+    #[resource] look: &mut Look,
 ) {
     // Capture the input key
     if let Some(key) = *key {
-        // Map the key into a point delta
-        let delta = match key {
-            VirtualKeyCode::Left => Point::new(-1, 0),
-            VirtualKeyCode::Right => Point::new(1, 0),
-            VirtualKeyCode::Up => Point::new(0, -1),
-            VirtualKeyCode::Down => Point::new(0, 1),
-            _ => Point::zero(),
-        };
+        // Open the inventory screen, which doesn't take a turn
+        if key == VirtualKeyCode::I {
+            *turn_state = TurnState::ShowingInventory;
+            return;
+        }
+
+        // Open the character sheet, which doesn't take a turn either
+        if key == VirtualKeyCode::C {
+            *turn_state = TurnState::ShowingCharacter;
+            return;
+        }
+
+        // Show or hide the minimap, which doesn't take a turn either
+        if key == VirtualKeyCode::M {
+            minimap.toggle();
+            return;
+        }
 
         // Get the player
         let mut players = <(Entity, &Point)>::query().filter(component::<Player>());
-        let (player_entity, destination) = players
+        let (player_entity, player_pos) = players
             .iter(ecs)
-            .find_map(|(entity, pos)| Some((*entity, *pos + delta)))
+            .map(|(entity, pos)| (*entity, *pos))
+            .next()
             .unwrap();
 
+        // Look around the map, starting from the player
+        if key == VirtualKeyCode::L {
+            look.cursor = player_pos;
+            *turn_state = TurnState::Looking;
+            return;
+        }
+
+        // Ready a spell, if the player knows it and has the mana to cast it. Spells that can only
+        // target the caster are cast straight away, while the rest are aimed first
+        let spell = match key {
+            VirtualKeyCode::F => Some(Spell::Firebolt),
+            VirtualKeyCode::B => Some(Spell::Blink),
+            VirtualKeyCode::H => Some(Spell::Heal),
+            _ => None,
+        };
+        if let Some(spell) = spell {
+            if can_cast(ecs, player_entity, spell) {
+                if spell.range() == 0 {
+                    commands.push((
+                        (),
+                        WantsToCast {
+                            caster: player_entity,
+                            spell,
+                            target: player_pos,
+                        },
+                    ));
+                    *turn_state = TurnState::PlayerTurn;
+                } else {
+                    *targeting = Targeting {
+                        spell,
+                        cursor: player_pos,
+                    };
+                    *turn_state = TurnState::Targeting;
+                }
+            }
+            return;
+        }
+
+        // Map the key into a point delta, or an action that doesn't involve moving
+        let (delta, did_act) = match key {
+            VirtualKeyCode::Left => (Point::new(-1, 0), false),
+            VirtualKeyCode::Right => (Point::new(1, 0), false),
+            VirtualKeyCode::Up => (Point::new(0, -1), false),
+            VirtualKeyCode::Down => (Point::new(0, 1), false),
+            VirtualKeyCode::G => (
+                Point::zero(),
+                pick_up_item(ecs, commands, log, player_entity, player_pos),
+            ),
+            VirtualKeyCode::S => (Point::zero(), search(ecs, commands, log, map, player_pos)),
+            VirtualKeyCode::Key1 => (Point::zero(), use_item(0, ecs, commands, player_entity)),
+            VirtualKeyCode::Key2 => (Point::zero(), use_item(1, ecs, commands, player_entity)),
+            VirtualKeyCode::Key3 => (Point::zero(), use_item(2, ecs, commands, player_entity)),
+            VirtualKeyCode::Key4 => (Point::zero(), use_item(3, ecs, commands, player_entity)),
+            VirtualKeyCode::Key5 => (Point::zero(), use_item(4, ecs, commands, player_entity)),
+            VirtualKeyCode::Key6 => (Point::zero(), use_item(5, ecs, commands, player_entity)),
+            VirtualKeyCode::Key7 => (Point::zero(), use_item(6, ecs, commands, player_entity)),
+            VirtualKeyCode::Key8 => (Point::zero(), use_item(7, ecs, commands, player_entity)),
+            VirtualKeyCode::Key9 => (Point::zero(), use_item(8, ecs, commands, player_entity)),
+            _ => (Point::zero(), false),
+        };
+
+        // If the player is confused, they may stumble off in a random direction instead
+        let delta = match find_status_effect(ecs, player_entity, StatusEffectKind::Confusion) {
+            Some(confusion) if delta != Point::zero() && rng.range(0, 10) < confusion.magnitude => {
+                random_direction(rng)
+            }
+            _ => delta,
+        };
+        let destination = player_pos + delta;
+
+        // Bumping into the shopkeeper opens the shop, which doesn't take a turn
+        if delta != Point::zero()
+            && <&Point>::query()
+                .filter(component::<Shopkeeper>())
+                .iter(ecs)
+                .any(|pos| *pos == destination)
+        {
+            *turn_state = TurnState::Shopping;
+            return;
+        }
+
         // Get the enemies
         let mut enemies = <(Entity, &Point)>::query().filter(component::<Enemy>());
 
         // If we have some direction of movement, add a command to either move or attack
-        let mut did_something = false;
+        let mut did_something = did_act;
         if delta.x != 0 || delta.y != 0 {
             let mut hit_something = false;
 
@@ -54,6 +172,30 @@ pub fn player_input(
                     ));
                 });
 
+            // A player who cleaves strikes every other enemy around them along with the one they hit
+            let cleaves = ecs
+                .entry_ref(player_entity)
+                .unwrap()
+                .get_component::<Cleave>()
+                .is_ok();
+            if hit_something && cleaves {
+                enemies
+                    .iter(ecs)
+                    .filter(|(_, pos)| {
+                        **pos != destination
+                            && DistanceAlg::Chebyshev.distance2d(player_pos, **pos) <= 1.0
+                    })
+                    .for_each(|(entity, _)| {
+                        commands.push((
+                            (),
+                            WantsToAttack {
+                                attacker: player_entity,
+                                victim: *entity,
+                            },
+                        ));
+                    });
+            }
+
             // If we didn't hit an enemy, issue a move command
             if !hit_something {
                 did_something = true;
@@ -67,15 +209,34 @@ pub fn player_input(
             }
         }
 
-        // If we didn't do anything, restore some player health
-        if !did_something {
-            if let Ok(mut health) = ecs
+        // If we didn't do anything, restore some player health and mana, unless they're too hungry to recover
+        let hungry = ecs
+            .entry_ref(player_entity)
+            .unwrap()
+            .get_component::<Hunger>()
+            .map(|hunger| {
+                hunger.state() == HungerState::Hungry || hunger.state() == HungerState::Starving
+            })
+            .unwrap_or(false);
+        if !did_something && !hungry {
+            let healing = ecs
+                .entry_ref(player_entity)
+                .unwrap()
+                .get_component::<RestHealing>()
+                .map_or(0, |healing| healing.0);
+            if let Ok(health) = ecs
                 .entry_mut(player_entity)
                 .unwrap()
                 .get_component_mut::<Health>()
             {
-                println!("Here");
-                health.current = i32::min(health.max, health.current + 1);
+                health.current = i32::min(health.max, health.current + healing);
+            }
+            if let Ok(mana) = ecs
+                .entry_mut(player_entity)
+                .unwrap()
+                .get_component_mut::<Mana>()
+            {
+                mana.current = i32::min(mana.max, mana.current + 1);
             }
         }
 
@@ -83,3 +244,89 @@ pub fn player_input(
         *turn_state = TurnState::PlayerTurn;
     }
 }
+
+// Picks up the items at the player's position, returning whether there were any
+fn pick_up_item(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    log: &mut GameLog,
+    player: Entity,
+    player_pos: Point,
+) -> bool {
+    let mut picked_up = false;
+    let mut items = <(Entity, &Point, &Name)>::query().filter(component::<Item>());
+    items
+        .iter(ecs)
+        .filter(|(_, pos, _)| **pos == player_pos)
+        .for_each(|(item, _, name)| {
+            commands.remove_component::<Point>(*item);
+            commands.add_component(*item, Carried(player));
+            log.add(format!("You pick up the {}.", name.0));
+            picked_up = true;
+        });
+    picked_up
+}
+
+// Searches the area around the player, revealing any hidden traps in sight. Searching takes a turn
+// whether or not it turns anything up
+fn search(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    log: &mut GameLog,
+    map: &mut Map,
+    player_pos: Point,
+) -> bool {
+    let fov = <&FieldOfView>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .next()
+        .unwrap();
+    <(Entity, &Point)>::query()
+        .filter(component::<Trap>() & component::<Hidden>())
+        .iter(ecs)
+        .filter(|(_, pos)| {
+            fov.visible_tiles.contains(pos)
+                && DistanceAlg::Pythagoras.distance2d(player_pos, **pos) <= SEARCH_RADIUS
+        })
+        .for_each(|(trap, pos)| {
+            reveal_trap(*trap, *pos, map, commands);
+            log.add("You find a trap.");
+        });
+    true
+}
+
+// Determines if an entity knows a spell and has the mana to cast it
+fn can_cast(ecs: &SubWorld, caster: Entity, spell: Spell) -> bool {
+    let entry = ecs.entry_ref(caster).unwrap();
+    let known = entry
+        .get_component::<Spellbook>()
+        .map(|spellbook| spellbook.0.contains(&spell))
+        .unwrap_or(false);
+    let affordable = entry
+        .get_component::<Mana>()
+        .map(|mana| mana.current >= spell.cost())
+        .unwrap_or(false);
+    known && affordable
+}
+
+// Uses the nth usable item carried by the player, returning whether there was one. The amulet
+// can't be used, so it's skipped over
+fn use_item(n: usize, ecs: &SubWorld, commands: &mut CommandBuffer, player: Entity) -> bool {
+    let item = <(Entity, &Carried)>::query()
+        .filter(component::<Item>() & !component::<AmuletOfYala>())
+        .iter(ecs)
+        .filter(|(_, carried)| carried.0 == player)
+        .nth(n)
+        .map(|(item, _)| *item);
+
+    if let Some(item) = item {
+        commands.push((
+            (),
+            ActivateItem {
+                used_by: player,
+                item,
+            },
+        ));
+    }
+    item.is_some()
+}
@@ -1,3 +1,4 @@
+use super::alert::raise_alert;
 use crate::prelude::*;
 
 // A system that handles the random movement of entities so annotated
@@ -6,23 +7,35 @@ use crate::prelude::*;
 #[read_component(MovingRandomly)]
 #[read_component(Health)]
 #[read_component(Player)]
-pub fn random_move(ecs: &mut SubWorld, commands: &mut CommandBuffer) {
+#[read_component(FieldOfView)]
+#[read_component(Pack)]
+pub fn random_move(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] rng: &mut RandomNumberGenerator,
+) {
     // Find our random movers
     let mut random_movers = <(Entity, &Point, &MovingRandomly)>::query();
 
+    // Find the player position
+    let mut players = <&Point>::query().filter(component::<Player>());
+    let player_pos = *players.iter(ecs).next().unwrap();
+
+    // Any random mover that spots the player raises the alert
+    let mut watchers =
+        <(Entity, &Point, &FieldOfView)>::query().filter(component::<MovingRandomly>());
+    watchers
+        .iter(ecs)
+        .filter(|(_, _, fov)| fov.visible_tiles.contains(&player_pos))
+        .for_each(|(entity, pos, _)| raise_alert(*entity, *pos, player_pos, ecs, commands));
+
     // Find our living (health-bearing) entities
     let mut living_entities = <(Entity, &Point, &Health)>::query();
 
     // For each mover, generate a random direction, and move the mover if we can
     random_movers.iter(ecs).for_each(|(entity, pos, _)| {
         // Generate a new destination
-        let mut rng = RandomNumberGenerator::new();
-        let destination = match rng.range(0, 4) {
-            0 => Point::new(-1, 0),
-            1 => Point::new(1, 0),
-            2 => Point::new(0, -1),
-            _ => Point::new(0, 1),
-        } + *pos;
+        let destination = random_direction(rng) + *pos;
 
         // Determine if any of our living entities is attacking a player
         let mut attacking = false;
@@ -60,3 +73,13 @@ pub fn random_move(ecs: &mut SubWorld, commands: &mut CommandBuffer) {
         }
     });
 }
+
+// Picks a random direction in which to move
+pub fn random_direction(rng: &mut RandomNumberGenerator) -> Point {
+    match rng.range(0, 4) {
+        0 => Point::new(-1, 0),
+        1 => Point::new(1, 0),
+        2 => Point::new(0, -1),
+        _ => Point::new(0, 1),
+    }
+}
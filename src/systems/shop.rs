@@ -0,0 +1,167 @@
+use crate::prelude::*;
+
+// A system that handles the shop screen, from which the shopkeeper's stock can be bought and the
+// player's items sold for half their price
+#[system]
+#[read_component(Player)]
+#[read_component(Shopkeeper)]
+#[read_component(Item)]
+#[read_component(Carried)]
+#[read_component(Name)]
+#[read_component(Price)]
+#[write_component(Gold)]
+pub fn shop(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] log: &mut GameLog,
+) {
+    // Get the player and the shopkeeper
+    let player = *<Entity>::query()
+        .filter(component::<Player>())
+        .iter(ecs)
+        .next()
+        .unwrap();
+    let shopkeeper = match <Entity>::query()
+        .filter(component::<Shopkeeper>())
+        .iter(ecs)
+        .next()
+    {
+        Some(shopkeeper) => *shopkeeper,
+        None => {
+            *turn_state = TurnState::AwaitingInput;
+            return;
+        }
+    };
+    let player_gold = gold_of(ecs, player);
+    let shop_gold = gold_of(ecs, shopkeeper);
+
+    // Gather the shopkeeper's stock, and the player's items along with what they would fetch
+    let stock = items_carried_by(ecs, shopkeeper, 1);
+    let wares = items_carried_by(ecs, player, 2);
+
+    // Draw the shop screen
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(2);
+    let top = 15;
+    let height = (stock.len() + wares.len()) as i32 + 9;
+    draw_batch.draw_box(
+        Rect::with_size(40, top, 80, height),
+        ColorPair::new(WHITE, BLACK),
+    );
+    draw_batch.print_color_centered(top, " Shop ", ColorPair::new(YELLOW, BLACK));
+    draw_batch.print_color(
+        Point::new(42, top + 2),
+        format!("For sale (you have {} gold):", player_gold),
+        ColorPair::new(YELLOW, BLACK),
+    );
+    if stock.is_empty() {
+        draw_batch.print(Point::new(44, top + 3), "Sold out.");
+    }
+    stock.iter().enumerate().for_each(|(i, (_, name, price))| {
+        let color = if *price <= player_gold { WHITE } else { GREY };
+        draw_batch.print_color(
+            Point::new(44, top + 3 + i as i32),
+            format!("{}) {} - {} gold", (b'a' + i as u8) as char, name, price),
+            ColorPair::new(color, BLACK),
+        );
+    });
+    let y = top + 4 + stock.len().max(1) as i32;
+    draw_batch.print_color(
+        Point::new(42, y),
+        format!("Your items (the shopkeeper has {} gold):", shop_gold),
+        ColorPair::new(YELLOW, BLACK),
+    );
+    if wares.is_empty() {
+        draw_batch.print(Point::new(44, y + 1), "You have nothing to sell.");
+    }
+    wares.iter().enumerate().for_each(|(i, (_, name, price))| {
+        let color = if *price <= shop_gold { WHITE } else { GREY };
+        draw_batch.print_color(
+            Point::new(44, y + 1 + i as i32),
+            format!("{}) {} - {} gold", i + 1, name, price),
+            ColorPair::new(color, BLACK),
+        );
+    });
+    draw_batch.print_color_centered(
+        top + height - 1,
+        " Letter to buy, 1-9 to sell. Escape to leave. ",
+        ColorPair::new(YELLOW, BLACK),
+    );
+    draw_batch.submit(11000).expect("Batch error");
+
+    // Handle the input
+    if let Some(key) = *key {
+        let sale = match key {
+            VirtualKeyCode::Key1 => Some(0),
+            VirtualKeyCode::Key2 => Some(1),
+            VirtualKeyCode::Key3 => Some(2),
+            VirtualKeyCode::Key4 => Some(3),
+            VirtualKeyCode::Key5 => Some(4),
+            VirtualKeyCode::Key6 => Some(5),
+            VirtualKeyCode::Key7 => Some(6),
+            VirtualKeyCode::Key8 => Some(7),
+            VirtualKeyCode::Key9 => Some(8),
+            _ => None,
+        };
+        let purchase = letter_to_option(key);
+
+        if key == VirtualKeyCode::Escape {
+            *turn_state = TurnState::AwaitingInput;
+        } else if let Some(n) = sale {
+            // Sell one of the player's items to the shopkeeper
+            if let Some((item, name, price)) = wares.get(n) {
+                if *price <= shop_gold {
+                    trade(ecs, commands, *item, *price, shopkeeper, player);
+                    log.add(format!("You sell the {} for {} gold.", name, price));
+                }
+            }
+        } else if purchase >= 0 {
+            // Buy one of the shopkeeper's items
+            if let Some((item, name, price)) = stock.get(purchase as usize) {
+                if *price <= player_gold {
+                    trade(ecs, commands, *item, *price, player, shopkeeper);
+                    log.add(format!("You buy the {} for {} gold.", name, price));
+                }
+            }
+        }
+    }
+}
+
+// Gets the gold held by an entity
+fn gold_of(ecs: &SubWorld, entity: Entity) -> i32 {
+    ecs.entry_ref(entity)
+        .ok()
+        .and_then(|entry| entry.get_component::<Gold>().ok().map(|gold| gold.0))
+        .unwrap_or(0)
+}
+
+// Gathers the priced items carried by an entity, with their price divided by the specified amount
+fn items_carried_by(ecs: &SubWorld, owner: Entity, divisor: i32) -> Vec<(Entity, String, i32)> {
+    <(Entity, &Carried, &Name, &Price)>::query()
+        .filter(component::<Item>())
+        .iter(ecs)
+        .filter(|(_, carried, _, _)| carried.0 == owner)
+        .map(|(entity, _, name, price)| (*entity, name.0.clone(), price.0 / divisor))
+        .collect()
+}
+
+// Hands an item over to a buyer, and the price over to the seller
+fn trade(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    item: Entity,
+    price: i32,
+    buyer: Entity,
+    seller: Entity,
+) {
+    if let Ok(gold) = ecs.entry_mut(buyer).unwrap().get_component_mut::<Gold>() {
+        gold.0 -= price;
+    }
+    if let Ok(gold) = ecs.entry_mut(seller).unwrap().get_component_mut::<Gold>() {
+        gold.0 += price;
+    }
+    commands.remove_component::<Equipped>(item);
+    commands.add_component(item, Carried(buyer));
+}
@@ -0,0 +1,129 @@
+use super::combat::{name_of, slay};
+use crate::prelude::*;
+
+// The damage dealt by a firebolt to everything caught in its blast
+const FIREBOLT_DAMAGE: i32 = 2;
+
+// The health restored by a heal spell
+const HEAL_AMOUNT: i32 = 4;
+
+// The volume of the sound made by a firebolt's blast
+const FIREBOLT_VOLUME: i32 = 15;
+
+// A system that handles the casting of spells, spending the caster's mana to unleash their effects
+#[system]
+#[read_component(WantsToCast)]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(LootTable)]
+#[write_component(Health)]
+#[read_component(Name)]
+#[write_component(Mana)]
+pub fn spells(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] rng: &mut RandomNumberGenerator,
+    #[resource] log: &mut GameLog,
+) {
+    let casts: Vec<(Entity, WantsToCast)> = <(Entity, &WantsToCast)>::query()
+        .iter(ecs)
+        .map(|(entity, cast)| (*entity, *cast))
+        .collect();
+
+    casts.iter().for_each(|(message, cast)| {
+        commands.remove(*message);
+
+        // Spend the mana for the spell, which fizzles if the caster doesn't have enough
+        if let Ok(mana) = ecs
+            .entry_mut(cast.caster)
+            .unwrap()
+            .get_component_mut::<Mana>()
+        {
+            if mana.current < cast.spell.cost() {
+                return;
+            }
+            mana.current -= cast.spell.cost();
+        }
+        log.add(format!("You cast {}.", cast.spell.name()));
+
+        match cast.spell {
+            Spell::Firebolt => firebolt(ecs, commands, rng, log, cast),
+            Spell::Blink => {
+                commands.push((
+                    (),
+                    WantsToMove {
+                        entity: cast.caster,
+                        destination: cast.target,
+                    },
+                ));
+            }
+            Spell::Heal => {
+                if let Ok(health) = ecs
+                    .entry_mut(cast.caster)
+                    .unwrap()
+                    .get_component_mut::<Health>()
+                {
+                    health.current = i32::min(health.max, health.current + HEAL_AMOUNT);
+                }
+            }
+        }
+    });
+}
+
+// Hurls a firebolt at the target, burning everything caught in the blast except the caster
+fn firebolt(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    rng: &mut RandomNumberGenerator,
+    log: &mut GameLog,
+    cast: &WantsToCast,
+) {
+    // Show the bolt in flight
+    if let Ok(caster_pos) = ecs.entry_ref(cast.caster).unwrap().get_component::<Point>() {
+        let path = line2d(LineAlg::Bresenham, *caster_pos, cast.target)
+            .into_iter()
+            .filter(|pos| pos != caster_pos)
+            .collect();
+        commands.push(((), Projectile { path }));
+    }
+
+    // Burn everything in the blast
+    let area = cast.spell.area(cast.target);
+    let victims: Vec<Entity> = <(Entity, &Point)>::query()
+        .filter(component::<Health>())
+        .iter(ecs)
+        .filter(|(entity, pos)| **entity != cast.caster && area.contains(pos))
+        .map(|(entity, _)| *entity)
+        .collect();
+    victims.iter().for_each(|victim| {
+        let entry = ecs.entry_ref(*victim).unwrap();
+        let is_player = entry.get_component::<Player>().is_ok();
+        let mut killed = false;
+        if let Ok(health) = ecs
+            .entry_mut(*victim)
+            .unwrap()
+            .get_component_mut::<Health>()
+        {
+            let was_alive = health.current > 0;
+            health.current -= FIREBOLT_DAMAGE;
+            killed = was_alive && health.current < 1 && !is_player;
+        }
+        if killed {
+            log.add(format!("The {} burns to death.", name_of(ecs, *victim)));
+            let by_player = ecs
+                .entry_ref(cast.caster)
+                .is_ok_and(|entry| entry.get_component::<Player>().is_ok());
+            slay(ecs, commands, rng, *victim, by_player);
+            commands.remove(*victim);
+        }
+    });
+
+    // The blast can be heard a long way off
+    commands.push((
+        (),
+        Sound {
+            source: cast.target,
+            volume: FIREBOLT_VOLUME,
+        },
+    ));
+}
@@ -0,0 +1,133 @@
+use super::combat::{name_of, slay};
+use crate::prelude::*;
+
+// A system that ticks down status effects, applying their effect for the turn. Effects on the player
+// tick in the player's turn, and effects on monsters in the monsters' turn
+#[system]
+#[read_component(Player)]
+#[write_component(StatusEffect)]
+#[read_component(Point)]
+#[read_component(LootTable)]
+#[read_component(Name)]
+#[write_component(Health)]
+pub fn status_effects(
+    ecs: &mut SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] turn_state: &TurnState,
+    #[resource] rng: &mut RandomNumberGenerator,
+    #[resource] log: &mut GameLog,
+    #[resource] stats: &mut RunStats,
+) {
+    let is_player_turn = *turn_state == TurnState::PlayerTurn;
+
+    // Gather the effects whose targets act this turn, and remove those whose target is gone
+    let mut effects = <(Entity, &StatusEffect)>::query();
+    let effects: Vec<(Entity, StatusEffect)> = effects
+        .iter(ecs)
+        .filter_map(|(entity, effect)| match ecs.entry_ref(effect.target) {
+            Ok(target) => {
+                let is_player = target.get_component::<Player>().is_ok();
+                if is_player == is_player_turn {
+                    Some((*entity, *effect))
+                } else {
+                    None
+                }
+            }
+            Err(_) => {
+                commands.remove(*entity);
+                None
+            }
+        })
+        .collect();
+
+    effects.iter().for_each(|(entity, effect)| {
+        // Apply the effect to the target's health
+        let is_player = ecs
+            .entry_ref(effect.target)
+            .unwrap()
+            .get_component::<Player>()
+            .is_ok();
+        let mut killed = false;
+        if let Ok(health) = ecs
+            .entry_mut(effect.target)
+            .unwrap()
+            .get_component_mut::<Health>()
+        {
+            match effect.kind {
+                StatusEffectKind::Poison => {
+                    let was_alive = health.current > 0;
+                    health.current -= effect.magnitude;
+                    if health.current < 1 && !is_player {
+                        commands.remove(effect.target);
+                        killed = was_alive;
+                    } else if health.current < 1 {
+                        stats.player_killed_by("Poison");
+                    }
+                }
+                StatusEffectKind::Regeneration => {
+                    health.current = i32::min(health.max, health.current + effect.magnitude);
+                }
+                _ => {}
+            }
+        }
+        if killed {
+            log.add(format!(
+                "The {} succumbs to poison.",
+                name_of(ecs, effect.target)
+            ));
+            slay(ecs, commands, rng, effect.target, false);
+        }
+
+        // Tick the effect down, removing it once it wears off
+        if let Ok(status) = ecs
+            .entry_mut(*entity)
+            .unwrap()
+            .get_component_mut::<StatusEffect>()
+        {
+            status.remaining_turns -= 1;
+            if status.remaining_turns < 1 {
+                commands.remove(*entity);
+                if is_player {
+                    log.add(format!(
+                        "You are no longer {}.",
+                        effect.kind.name().to_lowercase()
+                    ));
+                }
+            }
+        }
+    });
+}
+
+// Afflicts an entity with a status effect. If it's already suffering an effect of the same kind, that
+// one is refreshed rather than stacking another on top of it
+pub fn afflict(ecs: &SubWorld, commands: &mut CommandBuffer, effect: StatusEffect) {
+    let existing = <(Entity, &StatusEffect)>::query()
+        .iter(ecs)
+        .find(|(_, existing)| existing.target == effect.target && existing.kind == effect.kind)
+        .map(|(entity, existing)| (*entity, *existing));
+    match existing {
+        Some((entity, existing)) => commands.add_component(
+            entity,
+            StatusEffect {
+                remaining_turns: i32::max(existing.remaining_turns, effect.remaining_turns),
+                magnitude: i32::max(existing.magnitude, effect.magnitude),
+                ..existing
+            },
+        ),
+        None => {
+            commands.push(((), effect));
+        }
+    }
+}
+
+// Finds the status effect of the specified kind afflicting an entity, if there is one
+pub fn find_status_effect(
+    ecs: &SubWorld,
+    target: Entity,
+    kind: StatusEffectKind,
+) -> Option<StatusEffect> {
+    <&StatusEffect>::query()
+        .iter(ecs)
+        .find(|effect| effect.target == target && effect.kind == kind)
+        .copied()
+}
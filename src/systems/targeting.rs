@@ -0,0 +1,118 @@
+use crate::prelude::*;
+
+// A system that handles aiming a spell, moving a cursor over the tiles the player can see and
+// drawing the area the spell would affect over the map
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(Health)]
+#[read_component(FieldOfView)]
+pub fn targeting(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] key: &Option<VirtualKeyCode>,
+    #[resource] turn_state: &mut TurnState,
+    #[resource] targeting: &mut Targeting,
+    #[resource] map: &Map,
+    #[resource] viewport: &Viewport,
+) {
+    // Get the player
+    let mut players = <(Entity, &Point, &FieldOfView)>::query().filter(component::<Player>());
+    let (player, player_pos, fov) = players.iter(ecs).next().unwrap();
+
+    // Handle the input, moving the cursor, casting the spell or cancelling it
+    if let Some(key) = *key {
+        let delta = match key {
+            VirtualKeyCode::Left => Point::new(-1, 0),
+            VirtualKeyCode::Right => Point::new(1, 0),
+            VirtualKeyCode::Up => Point::new(0, -1),
+            VirtualKeyCode::Down => Point::new(0, 1),
+            _ => Point::zero(),
+        };
+        if map.in_bounds(targeting.cursor + delta) {
+            targeting.cursor += delta;
+        }
+
+        match key {
+            VirtualKeyCode::Escape => {
+                *turn_state = TurnState::AwaitingInput;
+                return;
+            }
+            VirtualKeyCode::Return | VirtualKeyCode::Space
+                if is_valid_target(ecs, targeting, map, fov, *player_pos) =>
+            {
+                commands.push((
+                    (),
+                    WantsToCast {
+                        caster: *player,
+                        spell: targeting.spell,
+                        target: targeting.cursor,
+                    },
+                ));
+                *turn_state = TurnState::PlayerTurn;
+                return;
+            }
+            _ => {}
+        }
+    }
+
+    // Draw the area of effect over the map, in red if the spell can't be cast there
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(1);
+    let offset = Point::new(viewport.left_x, viewport.top_y);
+    let color = if is_valid_target(ecs, targeting, map, fov, *player_pos) {
+        CYAN
+    } else {
+        RED
+    };
+    targeting
+        .spell
+        .area(targeting.cursor)
+        .iter()
+        .filter(|pos| **pos != targeting.cursor && fov.visible_tiles.contains(pos))
+        .for_each(|pos| {
+            draw_batch.set(*pos - offset, ColorPair::new(color, BLACK), to_cp437('*'));
+        });
+    draw_batch.set(
+        targeting.cursor - offset,
+        ColorPair::new(color, BLACK),
+        to_cp437('X'),
+    );
+    draw_batch.submit(6000).expect("Batch error");
+
+    // Explain the controls
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(2);
+    draw_batch.print_color_centered(
+        SCREEN_HEIGHT * 2 - 2,
+        format!(
+            " Casting {}: cursor keys to aim, Enter to cast, Escape to cancel. ",
+            targeting.spell.name()
+        ),
+        ColorPair::new(YELLOW, BLACK),
+    );
+    draw_batch.submit(11000).expect("Batch error");
+}
+
+// Determines if the spell being aimed can be cast at the cursor, which must be on an open tile the
+// player can see, within the spell's range. Blinking also needs somewhere clear to land
+fn is_valid_target(
+    ecs: &SubWorld,
+    targeting: &Targeting,
+    map: &Map,
+    fov: &FieldOfView,
+    player_pos: Point,
+) -> bool {
+    let cursor = targeting.cursor;
+    let in_range =
+        DistanceAlg::Pythagoras.distance2d(player_pos, cursor) <= targeting.spell.range() as f32;
+    let occupied = <&Point>::query()
+        .filter(component::<Health>())
+        .iter(ecs)
+        .any(|pos| *pos == cursor);
+
+    in_range
+        && fov.visible_tiles.contains(&cursor)
+        && map.can_enter_tile(cursor)
+        && !(targeting.spell == Spell::Blink && occupied)
+}
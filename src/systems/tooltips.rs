@@ -6,6 +6,7 @@ use crate::prelude::*;
 #[read_component(Health)]
 #[read_component(FieldOfView)]
 #[read_component(Player)]
+#[read_component(Hidden)]
 pub fn tooltips(ecs: &SubWorld, #[resource] mouse_pos: &Point, #[resource] viewport: &Viewport) {
     // Set the render position
     let offset = Point::new(viewport.left_x, viewport.top_y);
@@ -20,7 +21,7 @@ pub fn tooltips(ecs: &SubWorld, #[resource] mouse_pos: &Point, #[resource] viewp
     let player_fov = player_fov_query.iter(ecs).next().unwrap();
 
     // Get the positions and render them
-    let mut positions = <(Entity, &Point, &Name)>::query();
+    let mut positions = <(Entity, &Point, &Name)>::query().filter(!component::<Hidden>());
     positions
         .iter(ecs)
         .filter(|(_, pos, _)| **pos == map_pos && player_fov.visible_tiles.contains(pos))
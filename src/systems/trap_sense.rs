@@ -0,0 +1,30 @@
+use super::movement::reveal_trap;
+use crate::prelude::*;
+
+// A system that reveals the hidden traps in view of a player with a sense for them
+#[system]
+#[read_component(Point)]
+#[read_component(Player)]
+#[read_component(FieldOfView)]
+#[read_component(TrapSense)]
+#[read_component(Trap)]
+#[read_component(Hidden)]
+pub fn trap_sense(
+    ecs: &SubWorld,
+    commands: &mut CommandBuffer,
+    #[resource] map: &mut Map,
+    #[resource] log: &mut GameLog,
+) {
+    let mut players =
+        <&FieldOfView>::query().filter(component::<Player>() & component::<TrapSense>());
+    if let Some(fov) = players.iter(ecs).next() {
+        <(Entity, &Point)>::query()
+            .filter(component::<Trap>() & component::<Hidden>())
+            .iter(ecs)
+            .filter(|(_, pos)| fov.visible_tiles.contains(pos))
+            .for_each(|(trap, pos)| {
+                reveal_trap(*trap, *pos, map, commands);
+                log.add("You spot a trap.");
+            });
+    }
+}
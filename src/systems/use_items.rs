@@ -0,0 +1,76 @@
+use super::combat::name_of;
+use super::status_effects::afflict;
+use crate::prelude::*;
+
+// A system that handles the use of items
+#[system]
+#[read_component(ActivateItem)]
+#[read_component(AmuletOfYala)]
+#[read_component(AppliesEffect)]
+#[read_component(Equippable)]
+#[read_component(Equipped)]
+#[read_component(Nourishes)]
+#[read_component(Hunger)]
+#[read_component(Name)]
+#[read_component(StatusEffect)]
+pub fn use_items(ecs: &SubWorld, commands: &mut CommandBuffer, #[resource] log: &mut GameLog) {
+    let mut activations = <(Entity, &ActivateItem)>::query();
+    activations.iter(ecs).for_each(|(message, activate)| {
+        if let Ok(item) = ecs.entry_ref(activate.item) {
+            let name = name_of(ecs, activate.item);
+            if item.get_component::<AmuletOfYala>().is_ok() {
+                // The amulet is the goal of the run, not something to be used up
+                log.add(format!("You can't use the {}.", name));
+            } else if let Ok(equippable) = item.get_component::<Equippable>() {
+                // Equipment is taken off if it's being worn, or else put on in place of whatever
+                // is already in its slot
+                if item.get_component::<Equipped>().is_ok() {
+                    commands.remove_component::<Equipped>(activate.item);
+                    log.add(format!("You take off the {}.", name));
+                } else {
+                    <(Entity, &Equipped)>::query()
+                        .iter(ecs)
+                        .filter(|(_, equipped)| {
+                            equipped.owner == activate.used_by && equipped.slot == equippable.slot
+                        })
+                        .for_each(|(worn, _)| commands.remove_component::<Equipped>(*worn));
+                    commands.add_component(
+                        activate.item,
+                        Equipped {
+                            owner: activate.used_by,
+                            slot: equippable.slot,
+                        },
+                    );
+                    log.add(format!("You equip the {}.", name));
+                }
+            } else {
+                // Apply the item's effect to the user, and use the item up
+                log.add(format!("You use the {}.", name));
+                if let Ok(effect) = item.get_component::<AppliesEffect>() {
+                    afflict(ecs, commands, effect.afflict(activate.used_by));
+                }
+
+                // Food staves off the hunger of whoever eats it
+                if let Ok(nourishes) = item.get_component::<Nourishes>() {
+                    if let Ok(hunger) = ecs
+                        .entry_ref(activate.used_by)
+                        .unwrap()
+                        .get_component::<Hunger>()
+                    {
+                        commands.add_component(
+                            activate.used_by,
+                            Hunger {
+                                turns: i32::min(
+                                    Hunger::MAX,
+                                    i32::max(hunger.turns, 0) + nourishes.0,
+                                ),
+                            },
+                        );
+                    }
+                }
+                commands.remove(activate.item);
+            }
+        }
+        commands.remove(*message);
+    });
+}
@@ -0,0 +1,16 @@
+use crate::prelude::*;
+
+// The spell being aimed while targeting, and the point the cursor is over
+pub struct Targeting {
+    pub spell: Spell,
+    pub cursor: Point,
+}
+
+impl Targeting {
+    pub fn new() -> Self {
+        Self {
+            spell: Spell::Firebolt,
+            cursor: Point::zero(),
+        }
+    }
+}
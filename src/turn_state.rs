@@ -1,9 +1,44 @@
 // An enumeration of possible turn states
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum TurnState {
+    MainMenu,
+    NewGame,
+    Options,
+    HighScores,
+    ChoosingReplay,
     AwaitingInput,
     PlayerTurn,
     MonsterTurn,
+    NextLevel,
+    PreviousLevel,
+    ShowingInventory,
+    ShowingCharacter,
+    Shopping,
+    Targeting,
+    Looking,
+    Paused,
+    ShowingControls,
+    ShowingMessages,
     GameOver,
     Victory,
 }
+
+impl TurnState {
+    // Determines if the game is waiting on the player to press a key
+    pub fn is_awaiting_player(&self) -> bool {
+        matches!(
+            self,
+            TurnState::AwaitingInput
+                | TurnState::ShowingInventory
+                | TurnState::ShowingCharacter
+                | TurnState::Shopping
+                | TurnState::Targeting
+                | TurnState::Looking
+        )
+    }
+
+    // Determines if the run has come to an end
+    pub fn is_over(&self) -> bool {
+        matches!(self, TurnState::GameOver | TurnState::Victory)
+    }
+}
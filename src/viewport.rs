@@ -6,22 +6,114 @@ pub struct Viewport {
     pub right_x: i32,
     pub top_y: i32,
     pub bottom_y: i32,
+    // How close, in tiles, the player may come to the edge of the viewport before it scrolls, or 0
+    // to keep the player in the center
+    pub deadzone: i32,
 }
 
 impl Viewport {
-    pub fn new(player_position: Point) -> Self {
-        Self {
-            left_x: player_position.x - DISPLAY_WIDTH / 2,
-            right_x: player_position.x + DISPLAY_WIDTH / 2,
-            top_y: player_position.y - DISPLAY_HEIGHT / 2,
-            bottom_y: player_position.y + DISPLAY_HEIGHT / 2,
+    pub fn new(player_position: Point, deadzone: i32) -> Self {
+        let mut viewport = Self {
+            left_x: 0,
+            right_x: 0,
+            top_y: 0,
+            bottom_y: 0,
+            deadzone,
+        };
+        viewport.center_on(player_position);
+        viewport
+    }
+
+    // Scrolls the viewport to keep a position, usually the player's, in view
+    pub fn follow(&mut self, player_position: Point) {
+        if self.deadzone == 0 {
+            self.center_on(player_position);
+            return;
         }
+
+        // Only scroll far enough to keep the player out of the deadzone around the edges, unless
+        // they've ended up off screen altogether, as after a teleport
+        let left_x = scroll_axis(self.left_x, player_position.x, DISPLAY_WIDTH, self.deadzone);
+        let top_y = scroll_axis(self.top_y, player_position.y, DISPLAY_HEIGHT, self.deadzone);
+        self.move_to(left_x, top_y);
+    }
+
+    fn center_on(&mut self, pos: Point) {
+        self.move_to(pos.x - DISPLAY_WIDTH / 2, pos.y - DISPLAY_HEIGHT / 2);
+    }
+
+    // Moves the top left corner of the viewport, keeping it within the bounds of the map
+    fn move_to(&mut self, left_x: i32, top_y: i32) {
+        self.left_x = clamp_axis(left_x, DISPLAY_WIDTH, SCREEN_WIDTH);
+        self.right_x = self.left_x + DISPLAY_WIDTH;
+        self.top_y = clamp_axis(top_y, DISPLAY_HEIGHT, SCREEN_HEIGHT);
+        self.bottom_y = self.top_y + DISPLAY_HEIGHT;
+    }
+}
+
+// Finds the start of the viewport along one axis that keeps a position out of the deadzone
+fn scroll_axis(start: i32, pos: i32, display: i32, deadzone: i32) -> i32 {
+    if pos < start || pos >= start + display {
+        pos - display / 2
+    } else if pos < start + deadzone {
+        pos - deadzone
+    } else if pos >= start + display - deadzone {
+        pos - display + deadzone + 1
+    } else {
+        start
+    }
+}
+
+// Keeps the start of the viewport along one axis within the map, or centers the map if it's
+// smaller than the viewport
+fn clamp_axis(start: i32, display: i32, map: i32) -> i32 {
+    if map <= display {
+        (map - display) / 2
+    } else {
+        start.clamp(0, map - display)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_axis_keeps_the_viewport_on_the_map() {
+        assert_eq!(clamp_axis(-5, 40, 80), 0);
+        assert_eq!(clamp_axis(10, 40, 80), 10);
+        assert_eq!(clamp_axis(60, 40, 80), 40);
+    }
+
+    #[test]
+    fn clamp_axis_centers_a_map_smaller_than_the_viewport() {
+        assert_eq!(clamp_axis(10, 40, 30), -5);
+        assert_eq!(clamp_axis(-10, 40, 40), 0);
+    }
+
+    #[test]
+    fn new_centers_on_the_player_within_the_map() {
+        let viewport = Viewport::new(Point::new(SCREEN_WIDTH / 2, SCREEN_HEIGHT / 2), 0);
+        assert_eq!(viewport.left_x, SCREEN_WIDTH / 2 - DISPLAY_WIDTH / 2);
+        assert_eq!(viewport.top_y, SCREEN_HEIGHT / 2 - DISPLAY_HEIGHT / 2);
+
+        let viewport = Viewport::new(Point::new(0, SCREEN_HEIGHT - 1), 0);
+        assert_eq!((viewport.left_x, viewport.right_x), (0, DISPLAY_WIDTH));
+        assert_eq!(
+            (viewport.top_y, viewport.bottom_y),
+            (SCREEN_HEIGHT - DISPLAY_HEIGHT, SCREEN_HEIGHT)
+        );
     }
 
-    pub fn on_player_move(&mut self, player_position: Point) {
-        self.left_x = player_position.x - DISPLAY_WIDTH / 2;
-        self.right_x = player_position.x + DISPLAY_WIDTH / 2;
-        self.top_y = player_position.y - DISPLAY_HEIGHT / 2;
-        self.bottom_y = player_position.y + DISPLAY_HEIGHT / 2;
+    #[test]
+    fn follow_only_scrolls_once_the_player_reaches_the_deadzone() {
+        let mut viewport = Viewport::new(Point::new(SCREEN_WIDTH / 2, SCREEN_HEIGHT / 2), 5);
+        let (left_x, top_y) = (viewport.left_x, viewport.top_y);
+
+        viewport.follow(Point::new(left_x + DISPLAY_WIDTH - 6, top_y + 5));
+        assert_eq!((viewport.left_x, viewport.top_y), (left_x, top_y));
+
+        viewport.follow(Point::new(left_x + DISPLAY_WIDTH - 5, top_y + 4));
+        assert_eq!((viewport.left_x, viewport.top_y), (left_x + 1, top_y - 1));
     }
 }
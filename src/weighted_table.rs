@@ -0,0 +1,67 @@
+use crate::prelude::*;
+
+// A table of weighted entries, from which one is picked at random with a chance in proportion to its
+// weight. Used for monster loot drops and for the monsters spawned on a level
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightedTable<T>(pub Vec<(i32, T)>);
+
+impl<T: Copy> WeightedTable<T> {
+    pub fn new(entries: Vec<(i32, T)>) -> Self {
+        Self(entries)
+    }
+
+    // Picks an entry from the table, or None if it's empty. Entries without a positive weight are
+    // never picked
+    pub fn roll(&self, rng: &mut RandomNumberGenerator) -> Option<T> {
+        let weighted = || self.0.iter().filter(|(weight, _)| *weight > 0);
+        let total_weight: i32 = weighted().map(|(weight, _)| weight).sum();
+        if total_weight <= 0 {
+            return None;
+        }
+        let mut roll = rng.range(0, total_weight);
+        for (weight, entry) in weighted() {
+            if roll < *weight {
+                return Some(*entry);
+            }
+            roll -= weight;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roll_finds_nothing_in_a_table_without_weight() {
+        let mut rng = RandomNumberGenerator::seeded(1);
+        assert_eq!(WeightedTable::<char>::new(Vec::new()).roll(&mut rng), None);
+        assert_eq!(WeightedTable::new(vec![(0, 'a')]).roll(&mut rng), None);
+        assert_eq!(
+            WeightedTable::new(vec![(-3, 'a'), (2, 'b')]).roll(&mut rng),
+            Some('b')
+        );
+    }
+
+    #[test]
+    fn roll_never_picks_entries_without_weight() {
+        let mut rng = RandomNumberGenerator::seeded(2);
+        let table = WeightedTable::new(vec![(5, 'a'), (-5, 'b'), (0, 'c'), (2, 'd')]);
+        for _ in 0..1000 {
+            assert!(matches!(table.roll(&mut rng), Some('a') | Some('d')));
+        }
+    }
+
+    #[test]
+    fn roll_picks_entries_in_proportion_to_their_weight() {
+        let mut rng = RandomNumberGenerator::seeded(3);
+        let table = WeightedTable::new(vec![(1, 0), (3, 1)]);
+        let mut counts = [0; 2];
+        for _ in 0..4000 {
+            counts[table.roll(&mut rng).unwrap()] += 1;
+        }
+        assert!((800..1200).contains(&counts[0]), "{:?}", counts);
+        assert!((2800..3200).contains(&counts[1]), "{:?}", counts);
+    }
+}